@@ -0,0 +1,51 @@
+use observer::{Observer, Observable, NotifyReport, Priority, Quarantined, Subscribers, SubscriptionId,
+               SubscriptionError, SubscriptionStats};
+
+/// Subject for a single measurement channel, e.g. `WeatherData::temperature`.
+/// Observers are notified only when the value differs from the previous one.
+pub struct Channel<T> {
+    last: Option<T>,
+    observers: Subscribers<T>,
+}
+impl<T: Clone + PartialEq> Channel<T> {
+    pub fn new() -> Self {
+        Channel {
+            last: None,
+            observers: Subscribers::new(),
+        }
+    }
+    /// The last value seen on the channel.
+    pub fn last(&self) -> Option<&T> {
+        self.last.as_ref()
+    }
+    pub fn stats(&self, id: SubscriptionId) -> Option<SubscriptionStats> {
+        self.observers.stats(id)
+    }
+    pub fn quarantined(&self) -> Vec<Quarantined> {
+        self.observers.quarantined()
+    }
+}
+impl<T: Clone + PartialEq> Default for Channel<T> {
+    fn default() -> Self {
+        Channel::new()
+    }
+}
+impl<T: Clone + PartialEq> Observable<T> for Channel<T> {
+    fn register_with_priority(&mut self, observer: Box<dyn Observer<T>>, priority: Priority) -> SubscriptionId {
+        self.observers.insert(observer, priority)
+    }
+    fn remove(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        self.observers.remove(id)
+    }
+    fn notify(&mut self, value: T) -> NotifyReport {
+        if self.last.as_ref() == Some(&value) {
+            return NotifyReport {
+                suppressed: true,
+                ..NotifyReport::default()
+            };
+        }
+        let report = self.observers.notify(&value);
+        self.last = Some(value);
+        report
+    }
+}
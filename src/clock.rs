@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time, injectable so time dependent code can be tested.
+pub trait Clock {
+    /// Monotonic time, used to measure intervals.
+    fn now(&self) -> Instant;
+    /// Wall-clock time, used to timestamp records.
+    fn system_time(&self) -> SystemTime;
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock that only moves when told to. Clones share the same time.
+/// Its wall-clock time starts at the Unix epoch unless given otherwise.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    base: Instant,
+    start: SystemTime,
+    offset: Arc<Mutex<Duration>>,
+}
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::starting_at(UNIX_EPOCH)
+    }
+    pub fn starting_at(start: SystemTime) -> Self {
+        ManualClock {
+            base: Instant::now(),
+            start,
+            offset: Arc::new(Mutex::new(Duration::from_secs(0))),
+        }
+    }
+    pub fn advance(&self, duration: Duration) {
+        *self.offset() += duration;
+    }
+    fn offset(&self) -> MutexGuard<'_, Duration> {
+        self.offset.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.base + *self.offset()
+    }
+    fn system_time(&self) -> SystemTime {
+        self.start + *self.offset()
+    }
+}
+
+/// Formats a wall-clock time as `YYYY-MM-DD hh:mm:ss` UTC.
+/// Times before the Unix epoch are shown as the epoch.
+pub fn format_utc(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    // Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60)
+}
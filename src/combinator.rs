@@ -0,0 +1,346 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use clock::{Clock, SystemClock};
+use observer::{Observer, Observable, ErrorPolicy, NotifyReport, Priority, Quarantined, Subscribers, SubscriptionId,
+               SubscriptionError, UpdateError};
+
+type Flush<U> = Box<dyn FnMut() -> Option<U>>;
+
+struct Relay<U> {
+    subscribers: Subscribers<U>,
+    flush: Option<Flush<U>>,
+    detached: bool,
+    report: Option<NotifyReport>,
+}
+
+/// Observable produced by an `ObservableExt` combinator.
+///
+/// The derived subject stays subscribed to its source while either the handle
+/// or any of its own observers is alive, so intermediate handles of a chain
+/// can be dropped.
+///
+/// Failures of its observers are handled by its own `ErrorPolicy` and never
+/// reach the source, see `Derived::take_report`.
+pub struct Derived<U> {
+    relay: Rc<RefCell<Relay<U>>>,
+}
+impl<U> Derived<U> {
+    fn new(flush: Option<Flush<U>>) -> Self {
+        Derived {
+            relay: Rc::new(RefCell::new(Relay {
+                subscribers: Subscribers::new(),
+                flush,
+                detached: false,
+                report: None,
+            })),
+        }
+    }
+    /// Emits a value held back by the combinator, e.g. the pending value of `debounce`.
+    pub fn flush(&mut self) -> Option<NotifyReport> {
+        let mut relay = self.relay.borrow_mut();
+        let value = relay.flush.as_mut().and_then(|flush| flush())?;
+        Some(relay.subscribers.notify(&value))
+    }
+    pub fn quarantined(&self) -> Vec<Quarantined> {
+        self.relay.borrow().subscribers.quarantined()
+    }
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.relay.borrow_mut().subscribers.set_error_policy(policy);
+    }
+    /// Takes the report of the latest value emitted on behalf of the source.
+    /// Only the latest one is kept.
+    pub fn take_report(&mut self) -> Option<NotifyReport> {
+        self.relay.borrow_mut().report.take()
+    }
+}
+impl<U> Observable<U> for Derived<U> {
+    fn register_with_priority(&mut self, observer: Box<dyn Observer<U>>, priority: Priority) -> SubscriptionId {
+        self.relay.borrow_mut().subscribers.insert(observer, priority)
+    }
+    fn remove(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        self.relay.borrow_mut().subscribers.remove(id)
+    }
+    fn notify(&mut self, value: U) -> NotifyReport {
+        self.relay.borrow_mut().subscribers.notify(&value)
+    }
+}
+
+impl<U> Drop for Derived<U> {
+    fn drop(&mut self) {
+        if let Ok(mut relay) = self.relay.try_borrow_mut() {
+            relay.detached = true;
+        }
+    }
+}
+
+/// Observer registered on the source, feeding a `Derived` subject.
+struct Feeder<U, F> {
+    name: String,
+    relay: Rc<RefCell<Relay<U>>>,
+    operator: F,
+}
+impl<T, U, F: FnMut(&T) -> Option<U>> Observer<T> for Feeder<U, F> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        let value = match (self.operator)(value) {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        let mut relay = self.relay.borrow_mut();
+        let report = relay.subscribers.notify(&value);
+        relay.report = Some(report);
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn is_closed(&self) -> bool {
+        let relay = self.relay.borrow();
+        relay.detached && relay.subscribers.is_empty()
+    }
+}
+
+/// Rx-style combinators available on every `Observable`.
+///
+/// Each combinator registers an observer on `self` and returns a `Derived`
+/// subject emitting the transformed values.
+pub trait ObservableExt<T: 'static>: Observable<T> + Sized {
+    /// Registers `operator` on `self`, values it returns are emitted by the derived subject.
+    fn derive<U, F>(&mut self, name: &str, operator: F) -> Derived<U>
+        where U: 'static,
+              F: FnMut(&T) -> Option<U> + 'static
+    {
+        let derived = Derived::new(None);
+        self.register(Box::new(Feeder {
+            name: name.to_string(),
+            relay: derived.relay.clone(),
+            operator,
+        }));
+        derived
+    }
+    fn map<U, F>(&mut self, mut function: F) -> Derived<U>
+        where U: 'static,
+              F: FnMut(&T) -> U + 'static
+    {
+        self.derive("map", move |value| Some(function(value)))
+    }
+    fn filter<P>(&mut self, mut predicate: P) -> Derived<T>
+        where T: Clone,
+              P: FnMut(&T) -> bool + 'static
+    {
+        self.derive("filter", move |value| if predicate(value) {
+            Some(value.clone())
+        } else {
+            None
+        })
+    }
+    /// Emits the running accumulation of the values, starting from `initial`.
+    fn scan<A, F>(&mut self, initial: A, mut function: F) -> Derived<A>
+        where A: Clone + 'static,
+              F: FnMut(&A, &T) -> A + 'static
+    {
+        let mut state = initial;
+        self.derive("scan", move |value| {
+            state = function(&state, value);
+            Some(state.clone())
+        })
+    }
+    /// Emits the last `size` values on every value, once `size` of them were seen.
+    fn window(&mut self, size: usize) -> Derived<Vec<T>>
+        where T: Clone
+    {
+        assert!(size > 0, "window size must be positive");
+        let mut window = VecDeque::with_capacity(size);
+        self.derive("window", move |value: &T| {
+            if window.len() == size {
+                window.pop_front();
+            }
+            window.push_back(value.clone());
+            if window.len() == size {
+                Some(window.iter().cloned().collect())
+            } else {
+                None
+            }
+        })
+    }
+    /// Emits non-overlapping batches of `size` values.
+    fn buffer(&mut self, size: usize) -> Derived<Vec<T>>
+        where T: Clone
+    {
+        assert!(size > 0, "buffer size must be positive");
+        let mut buffer = Vec::with_capacity(size);
+        self.derive("buffer", move |value: &T| {
+            buffer.push(value.clone());
+            if buffer.len() == size {
+                Some(buffer.split_off(0))
+            } else {
+                None
+            }
+        })
+    }
+    /// Emits a value, then ignores values arriving within `period` after it.
+    fn throttle(&mut self, period: Duration) -> Derived<T>
+        where T: Clone
+    {
+        self.throttle_with_clock(period, SystemClock)
+    }
+    fn throttle_with_clock<C>(&mut self, period: Duration, clock: C) -> Derived<T>
+        where T: Clone,
+              C: Clock + 'static
+    {
+        let mut last: Option<Instant> = None;
+        self.derive("throttle", move |value: &T| {
+            let now = clock.now();
+            match last {
+                Some(last) if now.duration_since(last) < period => None,
+                _ => {
+                    last = Some(now);
+                    Some(value.clone())
+                }
+            }
+        })
+    }
+    /// Emits a value only after no newer value arrived within `period`.
+    ///
+    /// There is no timer behind it: the held back value is emitted when the next
+    /// value arrives after the quiet period, or by `Derived::flush`.
+    fn debounce(&mut self, period: Duration) -> Derived<T>
+        where T: Clone
+    {
+        self.debounce_with_clock(period, SystemClock)
+    }
+    fn debounce_with_clock<C>(&mut self, period: Duration, clock: C) -> Derived<T>
+        where T: Clone,
+              C: Clock + 'static
+    {
+        let pending: Rc<RefCell<Option<(T, Instant)>>> = Rc::new(RefCell::new(None));
+        let derived = {
+            let pending = pending.clone();
+            Derived::new(Some(Box::new(move || pending.borrow_mut().take().map(|(value, _)| value))))
+        };
+        self.register(Box::new(Feeder {
+            name: String::from("debounce"),
+            relay: derived.relay.clone(),
+            operator: move |value: &T| {
+                let now = clock.now();
+                let mut pending = pending.borrow_mut();
+                let quiet = match *pending {
+                    Some((_, since)) => now.duration_since(since) >= period,
+                    None => false,
+                };
+                let released = if quiet { pending.take().map(|(value, _)| value) } else { None };
+                *pending = Some((value.clone(), now));
+                released
+            },
+        }));
+        derived
+    }
+    /// Skips values equal to the previous one.
+    fn distinct_until_changed(&mut self) -> Derived<T>
+        where T: Clone + PartialEq
+    {
+        let mut last: Option<T> = None;
+        self.derive("distinct_until_changed", move |value: &T| {
+            if last.as_ref() == Some(value) {
+                return None;
+            }
+            last = Some(value.clone());
+            Some(value.clone())
+        })
+    }
+}
+impl<T: 'static, S: Observable<T>> ObservableExt<T> for S {}
+
+/// Value coming from one of several sources, tagged with the source key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tagged<K, T> {
+    pub source: K,
+    pub value: T,
+}
+
+/// Registers `operator(index, value)` on every source, feeding the returned subject.
+fn join<K, T, U, F>(sources: Vec<(K, &mut dyn Observable<T>)>, name: &str, operator: F) -> Derived<U>
+    where T: 'static,
+          U: 'static,
+          F: Fn(usize, &T) -> Option<U> + 'static
+{
+    let derived = Derived::new(None);
+    let operator = Rc::new(operator);
+    for (index, (_, source)) in sources.into_iter().enumerate() {
+        let operator = operator.clone();
+        source.register(Box::new(Feeder {
+            name: name.to_string(),
+            relay: derived.relay.clone(),
+            operator: move |value: &T| operator(index, value),
+        }));
+    }
+    derived
+}
+
+/// Emits every value of every source as it arrives, tagged with the source key.
+pub fn merge<K, T>(sources: Vec<(K, &mut dyn Observable<T>)>) -> Derived<Tagged<K, T>>
+    where K: Clone + 'static,
+          T: Clone + 'static
+{
+    let keys: Vec<K> = sources.iter().map(|source| source.0.clone()).collect();
+    join(sources, "merge", move |index, value: &T| {
+        Some(Tagged {
+            source: keys[index].clone(),
+            value: value.clone(),
+        })
+    })
+}
+
+/// Pairs the n-th values of all sources: emits once every source produced its n-th value.
+/// Values of faster sources are queued until the slower ones catch up.
+pub fn zip<K, T>(sources: Vec<(K, &mut dyn Observable<T>)>) -> Derived<Vec<Tagged<K, T>>>
+    where K: Clone + 'static,
+          T: Clone + 'static
+{
+    let keys: Vec<K> = sources.iter().map(|source| source.0.clone()).collect();
+    let queues = RefCell::new(vec![VecDeque::new(); keys.len()]);
+    join(sources, "zip", move |index, value: &T| {
+        let mut queues = queues.borrow_mut();
+        queues[index].push_back(value.clone());
+        if queues.iter().any(|queue| queue.is_empty()) {
+            return None;
+        }
+        let values = queues.iter_mut()
+            .zip(keys.iter())
+            .map(|(queue, key)| {
+                Tagged {
+                    source: key.clone(),
+                    value: queue.pop_front().expect("queue is not empty"),
+                }
+            })
+            .collect();
+        Some(values)
+    })
+}
+
+/// Emits the latest value of every source whenever any source produces one,
+/// starting once every source produced at least one value.
+pub fn combine_latest<K, T>(sources: Vec<(K, &mut dyn Observable<T>)>) -> Derived<Vec<Tagged<K, T>>>
+    where K: Clone + 'static,
+          T: Clone + 'static
+{
+    let keys: Vec<K> = sources.iter().map(|source| source.0.clone()).collect();
+    let latest: RefCell<Vec<Option<T>>> = RefCell::new(vec![None; keys.len()]);
+    join(sources, "combine_latest", move |index, value: &T| {
+        let mut latest = latest.borrow_mut();
+        latest[index] = Some(value.clone());
+        latest.iter()
+            .zip(keys.iter())
+            .map(|(value, key)| {
+                value.clone().map(|value| {
+                    Tagged {
+                        source: key.clone(),
+                        value,
+                    }
+                })
+            })
+            .collect()
+    })
+}
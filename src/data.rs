@@ -0,0 +1,49 @@
+use std::ops::Add;
+
+use rand::{self, ChaChaRng, Rng, SeedableRng, ThreadRng};
+use rand::distributions::{IndependentSample, Range};
+use rand::distributions::range::SampleRange;
+
+/// Endless generator of random values in the `[base, base + delta)` range.
+///
+/// Values are integers or floating point numbers depending on the type of
+/// `base` and `delta`, e.g. `DataGen::new(10.0, 10.0)` yields fractional
+/// temperatures.
+///
+/// By default values are drawn from `rand::thread_rng()`; use `DataGen::seeded`
+/// or `DataGen::with_rng` when the sequence has to be reproducible.
+pub struct DataGen<T = i32, R = ThreadRng> {
+    rgen: R,
+    rang: Range<T>,
+}
+impl<T> DataGen<T>
+    where T: SampleRange + PartialOrd + Add<Output = T> + Copy
+{
+    pub fn new(base: T, delta: T) -> Self {
+        DataGen::with_rng(base, delta, rand::thread_rng())
+    }
+}
+impl<T> DataGen<T, ChaChaRng>
+    where T: SampleRange + PartialOrd + Add<Output = T> + Copy
+{
+    /// Generators created with the same seed yield the same sequence on every platform.
+    pub fn seeded(base: T, delta: T, seed: u64) -> Self {
+        let key = [seed as u32, (seed >> 32) as u32];
+        DataGen::with_rng(base, delta, ChaChaRng::from_seed(&key))
+    }
+}
+impl<T, R: Rng> DataGen<T, R>
+    where T: SampleRange + PartialOrd + Add<Output = T> + Copy
+{
+    /// Panics if `delta` is not positive.
+    pub fn with_rng(base: T, delta: T, rgen: R) -> Self {
+        let rang = Range::new(base, base + delta);
+        DataGen { rgen, rang }
+    }
+}
+impl<T: SampleRange + PartialOrd, R: Rng> Iterator for DataGen<T, R> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        Some(self.rang.ind_sample(&mut self.rgen))
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use weather::Pressure;
+
+/// Direction the pressure moves in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tendency {
+    Falling,
+    Steady,
+    Rising,
+}
+impl Tendency {
+    /// Tendency of a pressure change, steady while within `threshold` either way.
+    pub fn of_change(from: Pressure, to: Pressure, threshold: Pressure) -> Tendency {
+        if to.abs_diff(&from) <= threshold {
+            Tendency::Steady
+        } else if to > from {
+            Tendency::Rising
+        } else {
+            Tendency::Falling
+        }
+    }
+}
+impl fmt::Display for Tendency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Tendency::Falling => "falling",
+            Tendency::Steady => "steady",
+            Tendency::Rising => "rising",
+        };
+        f.write_str(text)
+    }
+}
+
+const FORECASTS: [&str; 32] = ["Settled fine",
+                               "Fine weather",
+                               "Fine, becoming less settled",
+                               "Fairly fine, showery later",
+                               "Showery, becoming more unsettled",
+                               "Unsettled, rain later",
+                               "Rain at times, worse later",
+                               "Rain at times, becoming very unsettled",
+                               "Very unsettled, rain",
+                               "Settled fine",
+                               "Fine weather",
+                               "Fine, possibly showers",
+                               "Fairly fine, showers likely",
+                               "Showery, bright intervals",
+                               "Changeable, some rain",
+                               "Unsettled, rain at times",
+                               "Rain at frequent intervals",
+                               "Very unsettled, rain",
+                               "Stormy, much rain",
+                               "Settled fine",
+                               "Fine weather",
+                               "Becoming fine",
+                               "Fairly fine, improving",
+                               "Fairly fine, possibly showers early",
+                               "Showery early, improving",
+                               "Changeable, mending",
+                               "Rather unsettled, clearing later",
+                               "Unsettled, probably improving",
+                               "Unsettled, short fine intervals",
+                               "Very unsettled, finer at times",
+                               "Stormy, possibly improving",
+                               "Stormy, much rain"];
+
+/// Short forecast by the simplified Zambretti rules from the sea-level
+/// pressure and its tendency over the last hours.
+pub fn zambretti(pressure: Pressure, tendency: Tendency) -> &'static str {
+    let hpa = pressure.hpa();
+    // Forecast number and the range of numbers for the tendency.
+    let (z, first, last) = match tendency {
+        Tendency::Falling => (127.0 - 0.12 * hpa, 1.0, 9.0),
+        Tendency::Steady => (144.0 - 0.13 * hpa, 10.0, 19.0),
+        Tendency::Rising => (185.0 - 0.16 * hpa, 20.0, 32.0),
+    };
+    let z = z.round().clamp(first, last);
+    FORECASTS[z as usize - 1]
+}
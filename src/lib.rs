@@ -0,0 +1,42 @@
+//! Observer pattern playground: a simulated weather station (`WeatherData`)
+//! notifying display widgets about fresh measurements.
+
+extern crate futures;
+extern crate rand;
+
+pub mod observer;
+pub mod channel;
+pub mod clock;
+pub mod combinator;
+pub mod data;
+pub mod sensor;
+pub mod weather;
+pub mod shared;
+pub mod worker;
+pub mod stream;
+pub mod weak;
+pub mod units;
+pub mod metrics;
+pub mod forecast;
+pub mod statistics;
+pub mod widget;
+
+pub use observer::{Observer, Observable, ErrorPolicy, Failure, Filtered, FnObserver, NotifyReport, Priority,
+                   Quarantined, Subscribers, SubscriptionId, SubscriptionError, SubscriptionStats,
+                   UpdateError};
+pub use channel::Channel;
+pub use clock::{Clock, SystemClock, ManualClock};
+pub use combinator::{Derived, ObservableExt, Tagged, merge, zip, combine_latest};
+pub use data::DataGen;
+pub use sensor::{Sensor, RandomSensor, ScriptedSensor, ReplaySensor};
+pub use weather::{WeatherData, WeatherRecord, Stamp, Deadband, Distinct, Temperature, TemperatureDelta, Humidity,
+                  Pressure, WindSpeed, WindDirection, Rainfall, UvIndex};
+pub use shared::{SharedWeatherData, SharedObserver};
+pub use worker::{WorkerObserver, WorkerControl, Overflow};
+pub use stream::{ObserverStream, StreamObserver};
+pub use weak::{WeakObserver, WeakSyncObserver};
+pub use units::{RangeError, TemperatureUnit, PressureUnit, SpeedUnit, RainfallUnit, Units};
+pub use metrics::Metrics;
+pub use forecast::Tendency;
+pub use statistics::{Quantity, RollingStatistics};
+pub use widget::{DisplayWidget, HistoryWindow, WidgetCurrent, WidgetForecast, WidgetStatistic};
@@ -0,0 +1,90 @@
+use combinator::{Derived, ObservableExt};
+use observer::Observable;
+use units::SpeedUnit;
+use weather::{WeatherRecord, Stamp, Temperature, Humidity, WindSpeed};
+
+/// Dew point by the Magnus formula, `None` for completely dry air.
+pub fn dew_point(temperature: Temperature, humidity: Humidity) -> Option<Temperature> {
+    let (b, c) = (17.62, 243.12);
+    let t = temperature.celsius();
+    let gamma = (humidity.percent() / 100.0).ln() + b * t / (c + t);
+    Temperature::from_celsius(c * gamma / (b - gamma)).ok()
+}
+
+/// Apparent temperature of hot and humid air, by the formulas of the US National Weather Service.
+pub fn heat_index(temperature: Temperature, humidity: Humidity) -> Temperature {
+    let (t, rh) = (temperature.fahrenheit(), humidity.percent());
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.049_015_23 * t + 10.143_331_27 * rh - 0.224_755_41 * t * rh -
+                        0.006_837_83 * t * t - 0.054_817_17 * rh * rh + 0.001_228_74 * t * t * rh +
+                        0.000_852_82 * t * rh * rh - 0.000_001_99 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+        index
+    };
+    // Far outside of their range the regressions may drop below absolute zero.
+    Temperature::from_fahrenheit(index).unwrap_or(temperature)
+}
+
+/// Canadian humidex, a dimensionless "feels like" number on the Celsius scale.
+pub fn humidex(temperature: Temperature, dew_point: Temperature) -> f64 {
+    let vapour_pressure = 6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / dew_point.kelvin())).exp();
+    temperature.celsius() + 0.5555 * (vapour_pressure - 10.0)
+}
+
+/// Mass of water vapour in g/m³.
+pub fn absolute_humidity(temperature: Temperature, humidity: Humidity) -> f64 {
+    let t = temperature.celsius();
+    let saturation = 6.112 * (17.67 * t / (t + 243.5)).exp();
+    saturation * humidity.percent() * 2.1674 / (273.15 + t)
+}
+
+/// Wind chill index of Environment Canada and the US National Weather Service,
+/// `None` outside of its range: above 10 °C or for wind under 4.8 km/h.
+pub fn wind_chill(temperature: Temperature, wind_speed: WindSpeed) -> Option<Temperature> {
+    let t = temperature.celsius();
+    let v = wind_speed.value(SpeedUnit::KilometersPerHour);
+    if t > 10.0 || v < 4.8 {
+        return None;
+    }
+    let v = v.powf(0.16);
+    Temperature::from_celsius(13.12 + 0.6215 * t - 11.37 * v + 0.3965 * t * v).ok()
+}
+
+/// Quantities derived from a single record.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Metrics {
+    /// Measured when the record has it, computed otherwise.
+    pub dew_point: Option<Temperature>,
+    pub heat_index: Temperature,
+    pub humidex: Option<f64>,
+    /// In g/m³.
+    pub absolute_humidity: f64,
+    /// Only with wind measured and within the range of the index.
+    pub wind_chill: Option<Temperature>,
+    pub stamp: Option<Stamp>,
+}
+impl Metrics {
+    pub fn from_record(record: &WeatherRecord) -> Metrics {
+        let dew_point = record.dew_point.or_else(|| dew_point(record.temperature, record.humidity));
+        Metrics {
+            dew_point,
+            heat_index: heat_index(record.temperature, record.humidity),
+            humidex: dew_point.map(|dew_point| humidex(record.temperature, dew_point)),
+            absolute_humidity: absolute_humidity(record.temperature, record.humidity),
+            wind_chill: record.wind_speed.and_then(|speed| wind_chill(record.temperature, speed)),
+            stamp: record.stamp,
+        }
+    }
+}
+
+/// Observable emitting the metrics of every record `source` emits.
+pub fn derive<S: Observable<WeatherRecord>>(source: &mut S) -> Derived<Metrics> {
+    source.derive("metrics", |record| Some(Metrics::from_record(record)))
+}
@@ -0,0 +1,419 @@
+use std::error::Error;
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use weak::WeakObserver;
+
+/// Opaque handle returned by `Observable::register`.
+/// Every id is unique within the process, so a handle issued by one subject
+/// is never mistaken for a subscription of another one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriptionId(u64);
+impl SubscriptionId {
+    pub fn unique() -> SubscriptionId {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        SubscriptionId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+impl fmt::Display for SubscriptionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SubscriptionError {
+    Unknown(SubscriptionId),
+}
+impl fmt::Display for SubscriptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SubscriptionError::Unknown(id) => write!(f, "unknown subscription {}", id),
+        }
+    }
+}
+impl Error for SubscriptionError {}
+
+/// Error reported by a failed `Observer::update`.
+pub type UpdateError = Box<dyn Error + Send + Sync>;
+
+pub trait Observer<T> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError>;
+    fn name(&self) -> String;
+    /// A closed observer is no longer interested in updates,
+    /// subjects drop it on the next notification instead of calling `update`.
+    fn is_closed(&self) -> bool {
+        false
+    }
+    /// Subjects call `update` only for values accepted here, see `Filtered`.
+    fn accepts(&mut self, _value: &T) -> bool {
+        true
+    }
+}
+impl<T, O: Observer<T> + ?Sized> Observer<T> for Box<O> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        (**self).update(value)
+    }
+    fn name(&self) -> String {
+        (**self).name()
+    }
+    fn is_closed(&self) -> bool {
+        (**self).is_closed()
+    }
+    fn accepts(&mut self, value: &T) -> bool {
+        (**self).accepts(value)
+    }
+}
+
+/// Observer that is only notified about values matching the predicate.
+pub struct Filtered<O, P> {
+    observer: O,
+    predicate: P,
+}
+impl<O, P> Filtered<O, P> {
+    pub fn new(observer: O, predicate: P) -> Self {
+        Filtered {
+            observer,
+            predicate,
+        }
+    }
+}
+impl<T, O: Observer<T>, P: FnMut(&T) -> bool> Observer<T> for Filtered<O, P> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        self.observer.update(value)
+    }
+    fn name(&self) -> String {
+        self.observer.name()
+    }
+    fn is_closed(&self) -> bool {
+        self.observer.is_closed()
+    }
+    fn accepts(&mut self, value: &T) -> bool {
+        (self.predicate)(value) && self.observer.accepts(value)
+    }
+}
+
+/// Adapter turning a closure into an observer.
+pub struct FnObserver<F> {
+    name: String,
+    function: F,
+}
+impl<F> FnObserver<F> {
+    pub fn new(function: F) -> Self {
+        FnObserver::named("closure", function)
+    }
+    pub fn named<Name: Into<String>>(name: Name, function: F) -> Self {
+        FnObserver {
+            name: name.into(),
+            function,
+        }
+    }
+}
+impl<T, F: FnMut(&T)> Observer<T> for FnObserver<F> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        (self.function)(value);
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// What a subject does when an observer fails to handle an update.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Record the failure and keep notifying the remaining observers.
+    #[default]
+    Continue,
+    /// Record the failure and skip the remaining observers.
+    StopOnFirst,
+    /// Like `Continue`, but unsubscribe an observer after it failed
+    /// the given number of updates in a row.
+    Unsubscribe(usize),
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub id: SubscriptionId,
+    pub name: String,
+    pub error: UpdateError,
+}
+
+/// Counters kept by a subject for every subscription.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubscriptionStats {
+    /// Values passed to `update`.
+    pub delivered: usize,
+    /// Values rejected by `Observer::accepts`.
+    pub filtered: usize,
+    /// Failed updates.
+    pub failures: usize,
+}
+
+/// Observer taken out of service because its `update` panicked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quarantined {
+    pub id: SubscriptionId,
+    pub name: String,
+    /// Panic message, if the payload was a string.
+    pub message: String,
+}
+
+/// Outcome of a single `Observable::notify` call.
+#[derive(Debug, Default)]
+pub struct NotifyReport {
+    /// Number of observers whose `update` was called.
+    pub delivered: usize,
+    /// Number of observers that did not accept the value.
+    pub filtered: usize,
+    pub failures: Vec<Failure>,
+    /// Observers removed by `ErrorPolicy::Unsubscribe` during this notification.
+    pub unsubscribed: Vec<SubscriptionId>,
+    /// Closed observers dropped during this notification.
+    pub pruned: Vec<SubscriptionId>,
+    /// The subject decided not to notify anybody, e.g. because nothing changed.
+    pub suppressed: bool,
+    /// Observers quarantined during this notification.
+    pub quarantined: Vec<Quarantined>,
+}
+impl NotifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty() && self.quarantined.is_empty()
+    }
+    /// Adds the outcome of a nested notification, e.g. of a per-channel subject.
+    /// `suppressed` is left as is.
+    pub fn merge(&mut self, other: NotifyReport) {
+        self.delivered += other.delivered;
+        self.filtered += other.filtered;
+        self.failures.extend(other.failures);
+        self.unsubscribed.extend(other.unsubscribed);
+        self.pruned.extend(other.pruned);
+        self.quarantined.extend(other.quarantined);
+    }
+}
+/// Observers with a higher priority are notified first,
+/// observers with equal priority are notified in registration order.
+pub type Priority = i32;
+pub const DEFAULT_PRIORITY: Priority = 0;
+
+pub trait Observable<T> {
+    fn register(&mut self, observer: Box<dyn Observer<T>>) -> SubscriptionId {
+        self.register_with_priority(observer, DEFAULT_PRIORITY)
+    }
+    fn register_with_priority(&mut self,
+                              observer: Box<dyn Observer<T>>,
+                              priority: Priority)
+                              -> SubscriptionId;
+    /// Registers an observer the caller keeps ownership of, see `WeakObserver`.
+    fn register_weak<O>(&mut self, observer: &Rc<RefCell<O>>) -> SubscriptionId
+        where Self: Sized,
+              O: Observer<T> + 'static,
+              T: 'static
+    {
+        self.register(Box::new(WeakObserver::new(observer)))
+    }
+    /// Registers a closure, e.g. `weather.register_fn(|record| println!("{:?}", record))`.
+    fn register_fn<F>(&mut self, function: F) -> SubscriptionId
+        where Self: Sized,
+              F: FnMut(&T) + 'static
+    {
+        self.register(Box::new(FnObserver::new(function)))
+    }
+    /// Registers an observer notified only about values matching the predicate.
+    fn register_filtered<P>(&mut self, observer: Box<dyn Observer<T>>, predicate: P) -> SubscriptionId
+        where Self: Sized,
+              P: FnMut(&T) -> bool + 'static,
+              T: 'static
+    {
+        self.register(Box::new(Filtered::new(observer, predicate)))
+    }
+    fn remove(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError>;
+    fn notify(&mut self, record: T) -> NotifyReport;
+}
+
+struct Subscription<O: ?Sized> {
+    id: SubscriptionId,
+    priority: Priority,
+    failures: usize,
+    stats: SubscriptionStats,
+    observer: Box<O>,
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("<non-string panic payload>")
+    }
+}
+
+/// Ordered list of observers, the building block of `Observable` subjects.
+///
+/// An observer whose `update` panics is moved to quarantine regardless of the
+/// error policy: it is no longer notified, but can be inspected, removed or restored.
+///
+/// `O` is the stored observer type, e.g. `dyn Observer<T> + Send` for subjects shared between threads.
+pub struct Subscribers<T, O: ?Sized = dyn Observer<T>> {
+    list: Vec<Subscription<O>>,
+    quarantine: Vec<(Quarantined, Subscription<O>)>,
+    policy: ErrorPolicy,
+    marker: PhantomData<fn(&T)>,
+}
+impl<T, O: Observer<T> + ?Sized> Subscribers<T, O> {
+    pub fn new() -> Self {
+        Subscribers {
+            list: Vec::new(),
+            quarantine: Vec::new(),
+            policy: ErrorPolicy::default(),
+            marker: PhantomData,
+        }
+    }
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.policy
+    }
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.policy = policy;
+    }
+    pub fn insert(&mut self, observer: Box<O>, priority: Priority) -> SubscriptionId {
+        let id = SubscriptionId::unique();
+        self.enlist(Subscription {
+            id,
+            priority,
+            failures: 0,
+            stats: SubscriptionStats::default(),
+            observer,
+        });
+        id
+    }
+    fn enlist(&mut self, subscription: Subscription<O>) {
+        let position = self.list
+            .iter()
+            .position(|s| s.priority < subscription.priority)
+            .unwrap_or(self.list.len());
+        self.list.insert(position, subscription);
+    }
+    /// Removes an active or quarantined observer.
+    pub fn remove(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        if let Some(position) = self.list.iter().position(|s| s.id == id) {
+            self.list.remove(position);
+            return Ok(());
+        }
+        if let Some(position) = self.quarantine.iter().position(|q| q.0.id == id) {
+            self.quarantine.remove(position);
+            return Ok(());
+        }
+        Err(SubscriptionError::Unknown(id))
+    }
+    /// Counters of an active or quarantined subscription.
+    pub fn stats(&self, id: SubscriptionId) -> Option<SubscriptionStats> {
+        self.list
+            .iter()
+            .chain(self.quarantine.iter().map(|q| &q.1))
+            .find(|s| s.id == id)
+            .map(|s| s.stats)
+    }
+    pub fn quarantined(&self) -> Vec<Quarantined> {
+        self.quarantine.iter().map(|q| q.0.clone()).collect()
+    }
+    /// Puts a quarantined observer back into service.
+    pub fn restore(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        match self.quarantine.iter().position(|q| q.0.id == id) {
+            Some(position) => {
+                let (_, mut subscription) = self.quarantine.remove(position);
+                subscription.failures = 0;
+                self.enlist(subscription);
+                Ok(())
+            }
+            None => Err(SubscriptionError::Unknown(id)),
+        }
+    }
+    pub fn notify(&mut self, value: &T) -> NotifyReport {
+        let mut report = NotifyReport::default();
+        let mut index = 0;
+        while index < self.list.len() {
+            if self.list[index].observer.is_closed() {
+                report.pruned.push(self.list.remove(index).id);
+                continue;
+            }
+            let subscription = &mut self.list[index];
+            let result = {
+                let observer = &mut subscription.observer;
+                panic::catch_unwind(AssertUnwindSafe(|| if observer.accepts(value) {
+                    Some(observer.update(value))
+                } else {
+                    None
+                }))
+            };
+            let error = match result {
+                Ok(None) => {
+                    subscription.stats.filtered += 1;
+                    report.filtered += 1;
+                    index += 1;
+                    continue;
+                }
+                Ok(Some(Ok(()))) => {
+                    subscription.stats.delivered += 1;
+                    report.delivered += 1;
+                    subscription.failures = 0;
+                    index += 1;
+                    continue;
+                }
+                Ok(Some(Err(error))) => {
+                    subscription.stats.delivered += 1;
+                    report.delivered += 1;
+                    error
+                }
+                Err(payload) => {
+                    let quarantined = Quarantined {
+                        id: subscription.id,
+                        name: subscription.observer.name(),
+                        message: panic_message(&*payload),
+                    };
+                    report.quarantined.push(quarantined.clone());
+                    let subscription = self.list.remove(index);
+                    self.quarantine.push((quarantined, subscription));
+                    continue;
+                }
+            };
+            subscription.failures += 1;
+            subscription.stats.failures += 1;
+            report.failures.push(Failure {
+                id: subscription.id,
+                name: subscription.observer.name(),
+                error,
+            });
+            match self.policy {
+                ErrorPolicy::Continue => index += 1,
+                ErrorPolicy::StopOnFirst => break,
+                ErrorPolicy::Unsubscribe(limit) => {
+                    if subscription.failures >= limit {
+                        report.unsubscribed.push(subscription.id);
+                        self.list.remove(index);
+                    } else {
+                        index += 1;
+                    }
+                }
+            }
+        }
+        report
+    }
+    /// Number of active (not quarantined) observers.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+impl<T, O: Observer<T> + ?Sized> Default for Subscribers<T, O> {
+    fn default() -> Self {
+        Subscribers::new()
+    }
+}
@@ -0,0 +1,272 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::vec;
+
+use data::DataGen;
+use rand::{ChaChaRng, Rng, ThreadRng};
+use units::RangeError;
+use weather::{WeatherRecord, Temperature, WindSpeed, WindDirection, Rainfall, UvIndex};
+
+/// Source of measurements for `WeatherData`.
+pub trait Sensor {
+    /// Returns the next reading or `None` when the source is exhausted.
+    fn read(&mut self) -> Option<WeatherRecord>;
+}
+
+/// ********************* RandomSensor *****************************
+/// Simulated sensor drawing every channel from its own `DataGen`.
+/// The optional channels are only measured once given a generator.
+/// Reading stops at the first value out of range, see `RandomSensor::error`.
+pub struct RandomSensor<R = ThreadRng> {
+    temperature: DataGen<f64, R>,
+    humidity: DataGen<f64, R>,
+    pressure: DataGen<f64, R>,
+    wind: Option<(DataGen<f64, R>, DataGen<f64, R>)>,
+    rainfall: Option<DataGen<f64, R>>,
+    uv_index: Option<DataGen<f64, R>>,
+    dew_point_spread: Option<DataGen<f64, R>>,
+    error: Option<RangeError>,
+}
+impl RandomSensor {
+    pub fn new() -> Self {
+        RandomSensor::with_generators(DataGen::new(10.0, 10.0),
+                                      DataGen::new(40.0, 60.0),
+                                      DataGen::new(700.0, 90.0))
+    }
+    /// Sensor measuring the optional channels as well.
+    pub fn all_channels() -> Self {
+        let mut sensor = RandomSensor::new();
+        sensor.set_wind(DataGen::new(0.0, 15.0), DataGen::new(0.0, 360.0));
+        sensor.set_rainfall(DataGen::new(0.0, 2.0));
+        sensor.set_uv_index(DataGen::new(0.0, 11.0));
+        sensor.set_dew_point_spread(DataGen::new(0.0, 10.0));
+        sensor
+    }
+}
+impl Default for RandomSensor {
+    fn default() -> Self {
+        RandomSensor::new()
+    }
+}
+impl RandomSensor<ChaChaRng> {
+    /// Sensors created with the same seed produce the same measurements.
+    pub fn seeded(seed: u64) -> Self {
+        let seed = seed.wrapping_mul(8);
+        RandomSensor::with_generators(DataGen::seeded(10.0, 10.0, seed),
+                                      DataGen::seeded(40.0, 60.0, seed.wrapping_add(1)),
+                                      DataGen::seeded(700.0, 90.0, seed.wrapping_add(2)))
+    }
+    /// Seeded sensor measuring the optional channels as well.
+    pub fn seeded_all_channels(seed: u64) -> Self {
+        let mut sensor = RandomSensor::seeded(seed);
+        let seed = seed.wrapping_mul(8);
+        sensor.set_wind(DataGen::seeded(0.0, 15.0, seed.wrapping_add(3)),
+                        DataGen::seeded(0.0, 360.0, seed.wrapping_add(4)));
+        sensor.set_rainfall(DataGen::seeded(0.0, 2.0, seed.wrapping_add(5)));
+        sensor.set_uv_index(DataGen::seeded(0.0, 11.0, seed.wrapping_add(6)));
+        sensor.set_dew_point_spread(DataGen::seeded(0.0, 10.0, seed.wrapping_add(7)));
+        sensor
+    }
+}
+impl<R: Rng> RandomSensor<R> {
+    pub fn with_generators(temperature: DataGen<f64, R>,
+                           humidity: DataGen<f64, R>,
+                           pressure: DataGen<f64, R>)
+                           -> Self {
+        RandomSensor {
+            temperature,
+            humidity,
+            pressure,
+            wind: None,
+            rainfall: None,
+            uv_index: None,
+            dew_point_spread: None,
+            error: None,
+        }
+    }
+    /// Wind speed in m/s and the direction it blows from in degrees.
+    pub fn set_wind(&mut self, speed: DataGen<f64, R>, direction: DataGen<f64, R>) {
+        self.wind = Some((speed, direction));
+    }
+    /// Rain in mm fallen between two readings.
+    pub fn set_rainfall(&mut self, rainfall: DataGen<f64, R>) {
+        self.rainfall = Some(rainfall);
+    }
+    pub fn set_uv_index(&mut self, uv_index: DataGen<f64, R>) {
+        self.uv_index = Some(uv_index);
+    }
+    /// Dew point is reported as the temperature minus the generated spread in °C.
+    pub fn set_dew_point_spread(&mut self, spread: DataGen<f64, R>) {
+        self.dew_point_spread = Some(spread);
+    }
+    /// The generated value out of range that stopped the readings, if any.
+    pub fn error(&self) -> Option<&RangeError> {
+        self.error.as_ref()
+    }
+}
+// Truncates a generated value to the resolution of the simulated sensor.
+fn tenths(value: f64) -> f64 {
+    (value * 10.0).floor() / 10.0
+}
+// Next value of an optional channel, `Some(None)` when it is not measured.
+fn next_tenths<R: Rng>(generator: Option<&mut DataGen<f64, R>>) -> Option<Option<f64>> {
+    match generator {
+        Some(generator) => generator.next().map(|value| Some(tenths(value))),
+        None => Some(None),
+    }
+}
+/// Generated values are read as °C, %, mmHg, m/s, degrees and mm with a
+/// resolution of a tenth, like a typical consumer sensor.
+impl<R: Rng> Sensor for RandomSensor<R> {
+    fn read(&mut self) -> Option<WeatherRecord> {
+        if self.error.is_some() {
+            return None;
+        }
+        let (temperature, humidity, pressure) = (tenths(self.temperature.next()?),
+                                                 tenths(self.humidity.next()?),
+                                                 tenths(self.pressure.next()?));
+        let wind = match self.wind {
+            Some((ref mut speed, ref mut direction)) => Some((tenths(speed.next()?), tenths(direction.next()?))),
+            None => None,
+        };
+        let rainfall = next_tenths(self.rainfall.as_mut())?;
+        let uv_index = next_tenths(self.uv_index.as_mut())?;
+        let spread = next_tenths(self.dew_point_spread.as_mut())?;
+
+        let measure = || -> Result<WeatherRecord, RangeError> {
+            let mut record = WeatherRecord::from_readings(temperature, humidity, pressure)?;
+            if let Some((speed, direction)) = wind {
+                record.wind_speed = Some(WindSpeed::from_meters_per_second(speed)?);
+                record.wind_direction = Some(WindDirection::from_degrees(direction)?);
+            }
+            record.rainfall = rainfall.map(Rainfall::from_millimeters).transpose()?;
+            record.uv_index = uv_index.map(UvIndex::new).transpose()?;
+            if let Some(spread) = spread {
+                record.dew_point = Some(Temperature::from_celsius(record.temperature.celsius() - spread)?);
+            }
+            Ok(record)
+        };
+        match measure() {
+            Ok(record) => Some(record),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+/// ********************* ScriptedSensor *****************************
+/// Plays back a prepared list of records, then stays exhausted.
+pub struct ScriptedSensor {
+    records: vec::IntoIter<WeatherRecord>,
+}
+impl ScriptedSensor {
+    pub fn new(records: Vec<WeatherRecord>) -> Self {
+        ScriptedSensor { records: records.into_iter() }
+    }
+}
+impl Sensor for ScriptedSensor {
+    fn read(&mut self) -> Option<WeatherRecord> {
+        self.records.next()
+    }
+}
+
+/// ********************* ReplaySensor *****************************
+/// Replays records stored as text, one `temperature,humidity,pressure` triple
+/// in °C, % and mmHg per line, optionally followed by `channel=value` pairs:
+/// `wind_speed` (m/s), `wind_direction` (degrees), `rainfall` (mm), `uv_index`
+/// and `dew_point` (°C), e.g. `21.4,45,760,wind_speed=3.5,uv_index=4`.
+/// Empty lines and lines starting with `#` are skipped.
+/// Reading stops at the first I/O error or malformed line, see `ReplaySensor::error`.
+pub struct ReplaySensor<B> {
+    lines: io::Lines<B>,
+    line: usize,
+    error: Option<io::Error>,
+}
+impl ReplaySensor<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(ReplaySensor::new(BufReader::new(File::open(path)?)))
+    }
+}
+impl<B: BufRead> ReplaySensor<B> {
+    pub fn new(reader: B) -> Self {
+        ReplaySensor {
+            lines: reader.lines(),
+            line: 0,
+            error: None,
+        }
+    }
+    /// The error that stopped the replay, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+    fn parse(&self, line: &str) -> io::Result<WeatherRecord> {
+        let error = |message: String| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", self.line, message))
+        };
+        let invalid = || {
+            error(format!("expected 'temperature,humidity,pressure[,channel=value]...', got '{}'",
+                          line))
+        };
+        let number = |field: &str| field.trim().parse::<f64>().map_err(|_| invalid());
+        let range = |range: RangeError| error(range.to_string());
+
+        let mut fields = line.split(',');
+        let mut readings = [0.0; 3];
+        for reading in readings.iter_mut() {
+            *reading = number(fields.next().ok_or_else(invalid)?)?;
+        }
+        let mut record = WeatherRecord::from_readings(readings[0], readings[1], readings[2]).map_err(range)?;
+        for field in fields {
+            let mut pair = field.splitn(2, '=');
+            let (channel, value) = match (pair.next(), pair.next()) {
+                (Some(channel), Some(value)) => (channel.trim(), number(value)?),
+                _ => return Err(invalid()),
+            };
+            match channel {
+                "wind_speed" => {
+                    record.wind_speed = Some(WindSpeed::from_meters_per_second(value).map_err(range)?)
+                }
+                "wind_direction" => {
+                    record.wind_direction = Some(WindDirection::from_degrees(value).map_err(range)?)
+                }
+                "rainfall" => record.rainfall = Some(Rainfall::from_millimeters(value).map_err(range)?),
+                "uv_index" => record.uv_index = Some(UvIndex::new(value).map_err(range)?),
+                "dew_point" => record.dew_point = Some(Temperature::from_celsius(value).map_err(range)?),
+                _ => return Err(error(format!("unknown channel '{}'", channel))),
+            }
+        }
+        Ok(record)
+    }
+}
+impl<B: BufRead> Sensor for ReplaySensor<B> {
+    fn read(&mut self) -> Option<WeatherRecord> {
+        if self.error.is_some() {
+            return None;
+        }
+        for line in &mut self.lines {
+            self.line += 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(error) => {
+                    self.error = Some(error);
+                    return None;
+                }
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match self.parse(line) {
+                Ok(record) => return Some(record),
+                Err(error) => {
+                    self.error = Some(error);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use rand::{self, ChaChaRng};
+use observer::{Observer, Filtered, FnObserver, ErrorPolicy, NotifyReport, Priority, Quarantined, Subscribers,
+               SubscriptionId, SubscriptionError, SubscriptionStats, DEFAULT_PRIORITY};
+use sensor::{Sensor, RandomSensor};
+use stream::{self, ObserverStream};
+use weak::WeakSyncObserver;
+use clock::Clock;
+use weather::{Deadband, Distinct, Stamper, WeatherRecord};
+
+pub type SharedObserver = dyn Observer<WeatherRecord> + Send;
+
+/// Thread-safe counterpart of `WeatherData`, meant to be shared behind an `Arc`.
+///
+/// All methods take `&self`, so observers can be registered and removed from one
+/// thread while another thread reads the sensor and notifies.
+///
+/// Notifications are serialized: at most one `notify` runs at a time and it holds
+/// the observer list for its whole duration. `register`, `remove` and `restore`
+/// called while a notification is in flight wait until it completes, therefore:
+///
+/// * an observer registered during a notification first receives the next record;
+/// * once `remove` returns, the observer receives no further updates.
+///
+/// `measurements_changed` delivers records in the order of their sequence numbers.
+///
+/// Observers must not call back into the same subject from `update`, that deadlocks.
+pub struct SharedWeatherData<S = RandomSensor<ChaChaRng>> {
+    source: Mutex<(S, Stamper)>,
+    observers: Mutex<Subscribers<WeatherRecord, SharedObserver>>,
+}
+impl SharedWeatherData {
+    pub fn new() -> Self {
+        SharedWeatherData::with_sensor(RandomSensor::seeded(rand::random()))
+    }
+    /// Stations created with the same seed produce the same measurements.
+    pub fn seeded(seed: u64) -> Self {
+        SharedWeatherData::with_sensor(RandomSensor::seeded(seed))
+    }
+}
+impl Default for SharedWeatherData {
+    fn default() -> Self {
+        SharedWeatherData::new()
+    }
+}
+impl<S: Sensor> SharedWeatherData<S> {
+    pub fn with_sensor(sensor: S) -> Self {
+        SharedWeatherData {
+            source: Mutex::new((sensor, Stamper::new())),
+            observers: Mutex::new(Subscribers::new()),
+        }
+    }
+    fn source(&self) -> MutexGuard<'_, (S, Stamper)> {
+        self.source.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    // Observer panics are caught by `Subscribers`, so a poisoned lock still holds consistent data.
+    fn observers(&self) -> MutexGuard<'_, Subscribers<WeatherRecord, SharedObserver>> {
+        self.observers.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    /// Clock timestamping the records, `SystemClock` by default.
+    pub fn set_clock<C: Clock + Send + 'static>(&self, clock: C) {
+        self.source().1.set_clock(clock);
+    }
+    pub fn register(&self, observer: Box<SharedObserver>) -> SubscriptionId {
+        self.register_with_priority(observer, DEFAULT_PRIORITY)
+    }
+    pub fn register_with_priority(&self, observer: Box<SharedObserver>, priority: Priority) -> SubscriptionId {
+        self.observers().insert(observer, priority)
+    }
+    /// Registers an observer the caller keeps ownership of, see `WeakSyncObserver`.
+    pub fn register_weak<O>(&self, observer: &Arc<Mutex<O>>) -> SubscriptionId
+        where O: Observer<WeatherRecord> + Send + 'static
+    {
+        self.register(Box::new(WeakSyncObserver::new(observer)))
+    }
+    pub fn register_fn<F>(&self, function: F) -> SubscriptionId
+        where F: FnMut(&WeatherRecord) + Send + 'static
+    {
+        self.register(Box::new(FnObserver::new(function)))
+    }
+    /// Registers an observer notified only about records matching the predicate.
+    pub fn register_filtered<P>(&self, observer: Box<SharedObserver>, predicate: P) -> SubscriptionId
+        where P: FnMut(&WeatherRecord) -> bool + Send + 'static
+    {
+        self.register(Box::new(Filtered::new(observer, predicate)))
+    }
+    /// Registers an observer notified only about records exceeding the deadband
+    /// compared to the last record it received.
+    pub fn register_distinct(&self, observer: Box<SharedObserver>, deadband: Deadband) -> SubscriptionId {
+        self.register(Box::new(Distinct::new(observer, deadband)))
+    }
+    pub fn remove(&self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        self.observers().remove(id)
+    }
+    /// Subscribes a stream of records buffering up to `capacity` of them.
+    /// Dropping the stream unsubscribes it on the next notification.
+    pub fn subscribe_stream(&self, capacity: usize) -> ObserverStream<WeatherRecord> {
+        let (observer, stream) = stream::channel(capacity);
+        self.register(Box::new(observer));
+        stream
+    }
+    pub fn notify(&self, record: WeatherRecord) -> NotifyReport {
+        self.observers().notify(&record)
+    }
+    pub fn set_error_policy(&self, policy: ErrorPolicy) {
+        self.observers().set_error_policy(policy);
+    }
+    pub fn stats(&self, id: SubscriptionId) -> Option<SubscriptionStats> {
+        self.observers().stats(id)
+    }
+    pub fn quarantined(&self) -> Vec<Quarantined> {
+        self.observers().quarantined()
+    }
+    pub fn restore(&self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        self.observers().restore(id)
+    }
+    /// Reads the sensor, stamps the record and notifies observers.
+    /// Returns `None` when the sensor has no more measurements.
+    pub fn measurements_changed(&self) -> Option<NotifyReport> {
+        // The source stays locked until the record is delivered, keeping sequence order.
+        let mut source = self.source();
+        let record = source.0.read()?;
+        let record = source.1.stamp(record);
+        Some(self.notify(record))
+    }
+}
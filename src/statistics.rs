@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// Measured quantities `WidgetStatistic` keeps statistics of.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Quantity {
+    Temperature,
+    Humidity,
+    Pressure,
+    WindSpeed,
+    Rainfall,
+    UvIndex,
+    DewPoint,
+}
+
+/// Statistics of a sliding window of timed values, updated as values enter
+/// and leave the window instead of being recomputed from all of them on every change.
+///
+/// Values must leave in the order they entered. Variance and standard
+/// deviation are those of the window itself, not estimates for a larger population.
+#[derive(Clone, Debug, Default)]
+pub struct RollingStatistics {
+    // Oldest first.
+    values: VecDeque<(SystemTime, f64)>,
+    sorted: Vec<f64>,
+    // Times enter the sums as seconds since `origin`, the oldest value when
+    // the sums were last recomputed.
+    origin: Option<SystemTime>,
+    // Values removed since then; the sums are recomputed once they outnumber
+    // the values left, which bounds both the offsets and the rounding errors.
+    removed: usize,
+    mean_time: f64,
+    mean: f64,
+    // Sums of squared deviations from the means, see Welford's algorithm.
+    time_deviations: f64,
+    deviations: f64,
+    co_deviations: f64,
+}
+impl RollingStatistics {
+    pub fn new() -> Self {
+        RollingStatistics::default()
+    }
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    /// Adds the newest value, taken at `time`.
+    pub fn push(&mut self, time: SystemTime, value: f64) {
+        self.origin.get_or_insert(time);
+        self.values.push_back((time, value));
+        let index = self.sorted.partition_point(|&sorted| sorted < value);
+        self.sorted.insert(index, value);
+        let (count, offset) = (self.values.len() as f64, self.offset(time));
+        self.accumulate(count, offset, value);
+    }
+    /// Removes the oldest value.
+    pub fn pop_front(&mut self) -> Option<f64> {
+        let (time, value) = self.values.pop_front()?;
+        let index = self.sorted.partition_point(|&sorted| sorted < value);
+        self.sorted.remove(index);
+        self.removed += 1;
+        if self.removed >= self.values.len() {
+            self.recompute();
+            return Some(value);
+        }
+
+        let (count, time) = (self.values.len() as f64, self.offset(time));
+        let (time_delta, delta) = (time - self.mean_time, value - self.mean);
+        self.mean_time -= time_delta / count;
+        self.mean -= delta / count;
+        self.time_deviations -= time_delta * (time - self.mean_time);
+        self.deviations -= delta * (value - self.mean);
+        self.co_deviations -= time_delta * (value - self.mean);
+        Some(value)
+    }
+    pub fn min(&self) -> Option<f64> {
+        self.sorted.first().cloned()
+    }
+    pub fn max(&self) -> Option<f64> {
+        self.sorted.last().cloned()
+    }
+    pub fn mean(&self) -> Option<f64> {
+        self.non_empty().map(|_| self.mean)
+    }
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+    /// Value below which `percent` of the values lie, interpolated linearly
+    /// between the nearest ones. `percent` is clamped to [0, 100].
+    pub fn percentile(&self, percent: f64) -> Option<f64> {
+        self.non_empty()?;
+        let rank = percent.clamp(0.0, 100.0) / 100.0 * (self.sorted.len() - 1) as f64;
+        let (lower, upper) = (self.sorted[rank.floor() as usize], self.sorted[rank.ceil() as usize]);
+        Some(lower + (upper - lower) * rank.fract())
+    }
+    pub fn variance(&self) -> Option<f64> {
+        self.non_empty().map(|count| (self.deviations / count).max(0.0))
+    }
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+    /// Slope of the least-squares line through the values, per second.
+    /// `None` unless the values were taken at different times.
+    pub fn trend(&self) -> Option<f64> {
+        if self.len() < 2 || self.time_deviations <= 0.0 {
+            return None;
+        }
+        Some(self.co_deviations / self.time_deviations)
+    }
+    /// Change from the oldest to the newest value, per second.
+    /// `None` unless they were taken at different times.
+    pub fn rate_of_change(&self) -> Option<f64> {
+        let (&(first_time, first), &(last_time, last)) = (self.values.front()?, self.values.back()?);
+        let elapsed = self.offset(last_time) - self.offset(first_time);
+        if elapsed == 0.0 {
+            return None;
+        }
+        Some((last - first) / elapsed)
+    }
+    fn offset(&self, time: SystemTime) -> f64 {
+        let origin = self.origin.unwrap_or(time);
+        match time.duration_since(origin) {
+            Ok(after) => after.as_secs_f64(),
+            Err(before) => -before.duration().as_secs_f64(),
+        }
+    }
+    // Adds a value to the sums as the `count`th one.
+    fn accumulate(&mut self, count: f64, time: f64, value: f64) {
+        let (time_delta, delta) = (time - self.mean_time, value - self.mean);
+        self.mean_time += time_delta / count;
+        self.mean += delta / count;
+        self.time_deviations += time_delta * (time - self.mean_time);
+        self.deviations += delta * (value - self.mean);
+        self.co_deviations += time_delta * (value - self.mean);
+    }
+    fn recompute(&mut self) {
+        self.origin = self.values.front().map(|&(time, _)| time);
+        self.removed = 0;
+        self.mean_time = 0.0;
+        self.mean = 0.0;
+        self.time_deviations = 0.0;
+        self.deviations = 0.0;
+        self.co_deviations = 0.0;
+        for index in 0..self.values.len() {
+            let (time, value) = self.values[index];
+            let offset = self.offset(time);
+            self.accumulate((index + 1) as f64, offset, value);
+        }
+    }
+    fn non_empty(&self) -> Option<f64> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.values.len() as f64)
+        }
+    }
+}
+
@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use futures::Stream;
+use observer::{Observer, UpdateError};
+
+struct Buffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    lagged: usize,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+type Shared<T> = Arc<Mutex<Buffer<T>>>;
+
+fn lock<T>(shared: &Shared<T>) -> MutexGuard<'_, Buffer<T>> {
+    shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Creates a connected observer and stream pair.
+///
+/// The observer buffers up to `capacity` values for the stream. When the consumer
+/// falls behind, the oldest buffered values are discarded and counted as lag.
+/// The stream ends once the observer is dropped, e.g. removed from its subject;
+/// the observer reports itself closed once the stream is dropped.
+pub fn channel<T: Clone>(capacity: usize) -> (StreamObserver<T>, ObserverStream<T>) {
+    assert!(capacity > 0, "stream capacity must be positive");
+    let shared = Arc::new(Mutex::new(Buffer {
+        items: VecDeque::with_capacity(capacity),
+        capacity,
+        lagged: 0,
+        waker: None,
+        closed: false,
+    }));
+    (StreamObserver { shared: shared.clone() }, ObserverStream { shared })
+}
+
+/// Feeding half of `channel`, to be registered on a subject.
+pub struct StreamObserver<T> {
+    shared: Shared<T>,
+}
+impl<T: Clone> Observer<T> for StreamObserver<T> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        let mut buffer = lock(&self.shared);
+        if buffer.items.len() == buffer.capacity {
+            buffer.items.pop_front();
+            buffer.lagged += 1;
+        }
+        buffer.items.push_back(value.clone());
+        if let Some(waker) = buffer.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+    fn name(&self) -> String {
+        String::from("stream")
+    }
+    fn is_closed(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+}
+impl<T> Drop for StreamObserver<T> {
+    fn drop(&mut self) {
+        let mut buffer = lock(&self.shared);
+        buffer.closed = true;
+        if let Some(waker) = buffer.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Consuming half of `channel`.
+pub struct ObserverStream<T> {
+    shared: Shared<T>,
+}
+impl<T> ObserverStream<T> {
+    /// Number of values discarded because the stream was not polled fast enough,
+    /// since the last `take_lagged`.
+    pub fn lagged(&self) -> usize {
+        lock(&self.shared).lagged
+    }
+    /// Returns the number of values discarded since the previous call.
+    pub fn take_lagged(&mut self) -> usize {
+        let mut buffer = lock(&self.shared);
+        let lagged = buffer.lagged;
+        buffer.lagged = 0;
+        lagged
+    }
+    /// Number of values buffered and not yet consumed.
+    pub fn pending(&self) -> usize {
+        lock(&self.shared).items.len()
+    }
+}
+impl<T> Stream for ObserverStream<T> {
+    type Item = T;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        let mut buffer = lock(&self.shared);
+        if let Some(value) = buffer.items.pop_front() {
+            return Poll::Ready(Some(value));
+        }
+        if buffer.closed {
+            return Poll::Ready(None);
+        }
+        buffer.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
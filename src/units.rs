@@ -0,0 +1,453 @@
+use std::error::Error;
+use std::fmt;
+
+/// A value outside the physically valid range of a quantity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeError {
+    pub quantity: &'static str,
+    pub value: f64,
+    pub unit: &'static str,
+}
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} is out of range", self.quantity, self.value, self.unit)
+    }
+}
+impl Error for RangeError {}
+
+fn to_raw(value: f64, factor: f64, offset: f64) -> Option<i64> {
+    let raw = (value * factor + offset).round();
+    if raw.is_finite() && raw.abs() < i64::MAX as f64 {
+        Some(raw as i64)
+    } else {
+        None
+    }
+}
+
+/// ********************* Temperature *****************************
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+impl TemperatureUnit {
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+            TemperatureUnit::Kelvin => "K",
+        }
+    }
+    // Raw value = value * factor + offset.
+    fn scale(&self) -> (f64, f64) {
+        match *self {
+            TemperatureUnit::Celsius => (1800.0, 0.0),
+            TemperatureUnit::Fahrenheit => (1000.0, -32000.0),
+            TemperatureUnit::Kelvin => (1800.0, -491_670.0),
+        }
+    }
+}
+
+/// Temperature stored in 1/1800 °C, so whole and hundredth degrees of every
+/// supported scale convert without loss.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Temperature(i64);
+impl Temperature {
+    const ABSOLUTE_ZERO: i64 = -491_670;
+
+    pub fn new(value: f64, unit: TemperatureUnit) -> Result<Temperature, RangeError> {
+        let (factor, offset) = unit.scale();
+        match to_raw(value, factor, offset) {
+            Some(raw) if raw >= Temperature::ABSOLUTE_ZERO => Ok(Temperature(raw)),
+            _ => {
+                Err(RangeError {
+                    quantity: "temperature",
+                    value,
+                    unit: unit.symbol(),
+                })
+            }
+        }
+    }
+    pub fn from_celsius(value: f64) -> Result<Temperature, RangeError> {
+        Temperature::new(value, TemperatureUnit::Celsius)
+    }
+    pub fn from_fahrenheit(value: f64) -> Result<Temperature, RangeError> {
+        Temperature::new(value, TemperatureUnit::Fahrenheit)
+    }
+    pub fn from_kelvin(value: f64) -> Result<Temperature, RangeError> {
+        Temperature::new(value, TemperatureUnit::Kelvin)
+    }
+    pub fn value(&self, unit: TemperatureUnit) -> f64 {
+        let (factor, offset) = unit.scale();
+        (self.0 as f64 - offset) / factor
+    }
+    pub fn celsius(&self) -> f64 {
+        self.value(TemperatureUnit::Celsius)
+    }
+    pub fn fahrenheit(&self) -> f64 {
+        self.value(TemperatureUnit::Fahrenheit)
+    }
+    pub fn kelvin(&self) -> f64 {
+        self.value(TemperatureUnit::Kelvin)
+    }
+    pub fn abs_diff(&self, other: &Temperature) -> TemperatureDelta {
+        TemperatureDelta((self.0 - other.0).abs())
+    }
+}
+
+/// Difference between two temperatures in 1/1800 °C. Unlike `Temperature`
+/// it converts without the offsets of the scales: 1 °C is 1.8 °F and 1 K.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TemperatureDelta(i64);
+impl TemperatureDelta {
+    pub fn new(value: f64, unit: TemperatureUnit) -> Result<TemperatureDelta, RangeError> {
+        let (factor, _) = unit.scale();
+        match to_raw(value, factor, 0.0) {
+            Some(raw) => Ok(TemperatureDelta(raw)),
+            None => {
+                Err(RangeError {
+                    quantity: "temperature difference",
+                    value,
+                    unit: unit.symbol(),
+                })
+            }
+        }
+    }
+    pub fn from_celsius(value: f64) -> Result<TemperatureDelta, RangeError> {
+        TemperatureDelta::new(value, TemperatureUnit::Celsius)
+    }
+    pub fn from_fahrenheit(value: f64) -> Result<TemperatureDelta, RangeError> {
+        TemperatureDelta::new(value, TemperatureUnit::Fahrenheit)
+    }
+    pub fn from_kelvin(value: f64) -> Result<TemperatureDelta, RangeError> {
+        TemperatureDelta::new(value, TemperatureUnit::Kelvin)
+    }
+    pub fn value(&self, unit: TemperatureUnit) -> f64 {
+        let (factor, _) = unit.scale();
+        self.0 as f64 / factor
+    }
+    pub fn celsius(&self) -> f64 {
+        self.value(TemperatureUnit::Celsius)
+    }
+    pub fn fahrenheit(&self) -> f64 {
+        self.value(TemperatureUnit::Fahrenheit)
+    }
+    pub fn kelvin(&self) -> f64 {
+        self.value(TemperatureUnit::Kelvin)
+    }
+}
+
+/// ********************* Humidity *****************************
+/// Relative humidity stored in 1/100 %.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Humidity(i64);
+impl Humidity {
+    pub const SYMBOL: &'static str = "%";
+
+    pub fn from_percent(value: f64) -> Result<Humidity, RangeError> {
+        match to_raw(value, 100.0, 0.0) {
+            Some(raw) if (0..=10_000).contains(&raw) => Ok(Humidity(raw)),
+            _ => {
+                Err(RangeError {
+                    quantity: "humidity",
+                    value,
+                    unit: Humidity::SYMBOL,
+                })
+            }
+        }
+    }
+    pub fn percent(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+    pub fn abs_diff(&self, other: &Humidity) -> Humidity {
+        Humidity((self.0 - other.0).abs())
+    }
+}
+
+/// ********************* Pressure *****************************
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PressureUnit {
+    MillimetersOfMercury,
+    Hectopascals,
+    InchesOfMercury,
+}
+impl PressureUnit {
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            PressureUnit::MillimetersOfMercury => "mmHg",
+            PressureUnit::Hectopascals => "hPa",
+            PressureUnit::InchesOfMercury => "inHg",
+        }
+    }
+    fn factor(&self) -> f64 {
+        match *self {
+            PressureUnit::MillimetersOfMercury => 2_026_500.0,
+            PressureUnit::Hectopascals => 1_520_000.0,
+            PressureUnit::InchesOfMercury => 51_473_100.0,
+        }
+    }
+}
+
+/// Pressure stored in 1/15200 Pa, so whole and tenth mmHg and hPa as well as
+/// hundredth inHg convert without loss.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pressure(i64);
+impl Pressure {
+    pub fn new(value: f64, unit: PressureUnit) -> Result<Pressure, RangeError> {
+        match to_raw(value, unit.factor(), 0.0) {
+            Some(raw) if raw >= 0 => Ok(Pressure(raw)),
+            _ => {
+                Err(RangeError {
+                    quantity: "pressure",
+                    value,
+                    unit: unit.symbol(),
+                })
+            }
+        }
+    }
+    pub fn from_mmhg(value: f64) -> Result<Pressure, RangeError> {
+        Pressure::new(value, PressureUnit::MillimetersOfMercury)
+    }
+    pub fn from_hpa(value: f64) -> Result<Pressure, RangeError> {
+        Pressure::new(value, PressureUnit::Hectopascals)
+    }
+    pub fn from_inhg(value: f64) -> Result<Pressure, RangeError> {
+        Pressure::new(value, PressureUnit::InchesOfMercury)
+    }
+    pub fn value(&self, unit: PressureUnit) -> f64 {
+        self.0 as f64 / unit.factor()
+    }
+    pub fn mmhg(&self) -> f64 {
+        self.value(PressureUnit::MillimetersOfMercury)
+    }
+    pub fn hpa(&self) -> f64 {
+        self.value(PressureUnit::Hectopascals)
+    }
+    pub fn inhg(&self) -> f64 {
+        self.value(PressureUnit::InchesOfMercury)
+    }
+    pub fn abs_diff(&self, other: &Pressure) -> Pressure {
+        Pressure((self.0 - other.0).abs())
+    }
+}
+
+/// ********************* WindSpeed *****************************
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+}
+impl SpeedUnit {
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            SpeedUnit::MetersPerSecond => "m/s",
+            SpeedUnit::KilometersPerHour => "km/h",
+            SpeedUnit::MilesPerHour => "mph",
+            SpeedUnit::Knots => "kn",
+        }
+    }
+    fn factor(&self) -> f64 {
+        match *self {
+            SpeedUnit::MetersPerSecond => 36_000.0,
+            SpeedUnit::KilometersPerHour => 10_000.0,
+            SpeedUnit::MilesPerHour => 16_093.44,
+            SpeedUnit::Knots => 18_520.0,
+        }
+    }
+}
+
+/// Wind speed stored in 1/36000 m/s, so tenths of m/s, km/h and knots convert
+/// without loss; miles per hour are rounded.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindSpeed(i64);
+impl WindSpeed {
+    pub fn new(value: f64, unit: SpeedUnit) -> Result<WindSpeed, RangeError> {
+        match to_raw(value, unit.factor(), 0.0) {
+            Some(raw) if raw >= 0 => Ok(WindSpeed(raw)),
+            _ => {
+                Err(RangeError {
+                    quantity: "wind speed",
+                    value,
+                    unit: unit.symbol(),
+                })
+            }
+        }
+    }
+    pub fn from_meters_per_second(value: f64) -> Result<WindSpeed, RangeError> {
+        WindSpeed::new(value, SpeedUnit::MetersPerSecond)
+    }
+    pub fn value(&self, unit: SpeedUnit) -> f64 {
+        self.0 as f64 / unit.factor()
+    }
+    pub fn meters_per_second(&self) -> f64 {
+        self.value(SpeedUnit::MetersPerSecond)
+    }
+    pub fn abs_diff(&self, other: &WindSpeed) -> WindSpeed {
+        WindSpeed((self.0 - other.0).abs())
+    }
+}
+
+/// ********************* WindDirection *****************************
+/// Direction the wind blows from, stored in 1/10 degree clockwise from north.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindDirection(i64);
+impl WindDirection {
+    pub const SYMBOL: &'static str = "°";
+    const POINTS: [&'static str; 16] = ["N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW",
+                                        "WSW", "W", "WNW", "NW", "NNW"];
+
+    /// Accepts `[0, 360]` degrees, 360 being north as well as 0.
+    pub fn from_degrees(value: f64) -> Result<WindDirection, RangeError> {
+        match to_raw(value, 10.0, 0.0) {
+            Some(raw) if (0..=3600).contains(&raw) => Ok(WindDirection(raw % 3600)),
+            _ => {
+                Err(RangeError {
+                    quantity: "wind direction",
+                    value,
+                    unit: WindDirection::SYMBOL,
+                })
+            }
+        }
+    }
+    pub fn degrees(&self) -> f64 {
+        self.0 as f64 / 10.0
+    }
+    /// One of the 16 compass points, e.g. "WSW".
+    pub fn compass(&self) -> &'static str {
+        // Each point covers 22.5°, centered on its direction.
+        WindDirection::POINTS[((self.0 + 112) / 225 % 16) as usize]
+    }
+    /// Angle between two directions the short way round, at most 180°.
+    pub fn abs_diff(&self, other: &WindDirection) -> WindDirection {
+        let angle = (self.0 - other.0).abs();
+        WindDirection(angle.min(3600 - angle))
+    }
+}
+
+/// ********************* Rainfall *****************************
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RainfallUnit {
+    Millimeters,
+    Inches,
+}
+impl RainfallUnit {
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            RainfallUnit::Millimeters => "mm",
+            RainfallUnit::Inches => "in",
+        }
+    }
+    fn factor(&self) -> f64 {
+        match *self {
+            RainfallUnit::Millimeters => 1000.0,
+            RainfallUnit::Inches => 25_400.0,
+        }
+    }
+}
+
+/// Amount of rain stored in 1/1000 mm, so hundredths of an inch convert without loss.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rainfall(i64);
+impl Rainfall {
+    pub fn new(value: f64, unit: RainfallUnit) -> Result<Rainfall, RangeError> {
+        match to_raw(value, unit.factor(), 0.0) {
+            Some(raw) if raw >= 0 => Ok(Rainfall(raw)),
+            _ => {
+                Err(RangeError {
+                    quantity: "rainfall",
+                    value,
+                    unit: unit.symbol(),
+                })
+            }
+        }
+    }
+    pub fn from_millimeters(value: f64) -> Result<Rainfall, RangeError> {
+        Rainfall::new(value, RainfallUnit::Millimeters)
+    }
+    pub fn value(&self, unit: RainfallUnit) -> f64 {
+        self.0 as f64 / unit.factor()
+    }
+    pub fn millimeters(&self) -> f64 {
+        self.value(RainfallUnit::Millimeters)
+    }
+    pub fn abs_diff(&self, other: &Rainfall) -> Rainfall {
+        Rainfall((self.0 - other.0).abs())
+    }
+}
+
+/// ********************* UvIndex *****************************
+/// UV index stored in tenths.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UvIndex(i64);
+impl UvIndex {
+    pub fn new(value: f64) -> Result<UvIndex, RangeError> {
+        match to_raw(value, 10.0, 0.0) {
+            Some(raw) if raw >= 0 => Ok(UvIndex(raw)),
+            _ => {
+                Err(RangeError {
+                    quantity: "UV index",
+                    value,
+                    unit: "",
+                })
+            }
+        }
+    }
+    pub fn value(&self) -> f64 {
+        self.0 as f64 / 10.0
+    }
+    pub fn abs_diff(&self, other: &UvIndex) -> UvIndex {
+        UvIndex((self.0 - other.0).abs())
+    }
+}
+
+/// ********************* Units *****************************
+/// Units a widget displays measurements in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub pressure: PressureUnit,
+    pub wind_speed: SpeedUnit,
+    pub rainfall: RainfallUnit,
+}
+impl Units {
+    pub fn metric() -> Units {
+        Units {
+            temperature: TemperatureUnit::Celsius,
+            pressure: PressureUnit::Hectopascals,
+            wind_speed: SpeedUnit::KilometersPerHour,
+            rainfall: RainfallUnit::Millimeters,
+        }
+    }
+    pub fn imperial() -> Units {
+        Units {
+            temperature: TemperatureUnit::Fahrenheit,
+            pressure: PressureUnit::InchesOfMercury,
+            wind_speed: SpeedUnit::MilesPerHour,
+            rainfall: RainfallUnit::Inches,
+        }
+    }
+}
+/// The units of the simulated sensor: °C, mmHg, m/s and mm.
+impl Default for Units {
+    fn default() -> Units {
+        Units {
+            temperature: TemperatureUnit::Celsius,
+            pressure: PressureUnit::MillimetersOfMercury,
+            wind_speed: SpeedUnit::MetersPerSecond,
+            rainfall: RainfallUnit::Millimeters,
+        }
+    }
+}
+
+/// Formats a measurement with at most two decimals, dropping trailing zeros.
+pub fn format_value(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => String::from("0"),
+        _ => text.to_string(),
+    }
+}
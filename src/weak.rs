@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::rc::{self, Rc};
+use std::sync::{self, Arc, Mutex};
+
+use observer::{Observer, UpdateError};
+
+/// Observer registered by a weak reference, the caller keeps the `Rc` and can query it
+/// at any time. Once the caller drops every `Rc`, the subject prunes the observer.
+pub struct WeakObserver<O> {
+    observer: rc::Weak<RefCell<O>>,
+}
+impl<O> WeakObserver<O> {
+    pub fn new(observer: &Rc<RefCell<O>>) -> Self {
+        WeakObserver { observer: Rc::downgrade(observer) }
+    }
+}
+impl<T, O: Observer<T>> Observer<T> for WeakObserver<O> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        match self.observer.upgrade() {
+            Some(observer) => {
+                let mut observer = observer.try_borrow_mut()
+                    .map_err(|_| "observer is borrowed during update")?;
+                observer.update(value)
+            }
+            None => Ok(()),
+        }
+    }
+    fn name(&self) -> String {
+        match self.observer.upgrade() {
+            Some(observer) => {
+                match observer.try_borrow() {
+                    Ok(observer) => observer.name(),
+                    Err(_) => String::from("<borrowed>"),
+                }
+            }
+            None => String::from("<dropped>"),
+        }
+    }
+    fn is_closed(&self) -> bool {
+        self.observer.strong_count() == 0
+    }
+    fn accepts(&mut self, value: &T) -> bool {
+        match self.observer.upgrade() {
+            Some(observer) => observer.try_borrow_mut().map(|mut o| o.accepts(value)).unwrap_or(true),
+            None => false,
+        }
+    }
+}
+
+/// Thread-safe counterpart of `WeakObserver` for `Arc<Mutex<_>>` handles.
+pub struct WeakSyncObserver<O> {
+    observer: sync::Weak<Mutex<O>>,
+}
+impl<O> WeakSyncObserver<O> {
+    pub fn new(observer: &Arc<Mutex<O>>) -> Self {
+        WeakSyncObserver { observer: Arc::downgrade(observer) }
+    }
+}
+impl<T, O: Observer<T>> Observer<T> for WeakSyncObserver<O> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        match self.observer.upgrade() {
+            Some(observer) => {
+                let mut observer = observer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                observer.update(value)
+            }
+            None => Ok(()),
+        }
+    }
+    fn name(&self) -> String {
+        match self.observer.upgrade() {
+            Some(observer) => observer.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).name(),
+            None => String::from("<dropped>"),
+        }
+    }
+    fn is_closed(&self) -> bool {
+        self.observer.strong_count() == 0
+    }
+    fn accepts(&mut self, value: &T) -> bool {
+        match self.observer.upgrade() {
+            Some(observer) => observer.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).accepts(value),
+            None => false,
+        }
+    }
+}
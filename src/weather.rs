@@ -0,0 +1,301 @@
+use std::time::SystemTime;
+
+pub use units::{Temperature, TemperatureDelta, Humidity, Pressure, WindSpeed, WindDirection, Rainfall, UvIndex};
+use units::RangeError;
+use clock::{Clock, SystemClock};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct WeatherRecord {
+    pub temperature: Temperature,
+    pub humidity: Humidity,
+    pub pressure: Pressure,
+    /// Optional channels, `None` when the sensor does not measure them.
+    pub wind_speed: Option<WindSpeed>,
+    pub wind_direction: Option<WindDirection>,
+    /// Rain fallen since the previous reading.
+    pub rainfall: Option<Rainfall>,
+    pub uv_index: Option<UvIndex>,
+    pub dew_point: Option<Temperature>,
+    /// Set by `measurements_changed`; `None` for records built by hand.
+    pub stamp: Option<Stamp>,
+}
+impl WeatherRecord {
+    pub fn new() -> WeatherRecord {
+        WeatherRecord::default()
+    }
+    /// Builds a record from readings in °C, % and mmHg, the units of the simulated sensor.
+    pub fn from_readings(celsius: f64, percent: f64, mmhg: f64) -> Result<WeatherRecord, RangeError> {
+        Ok(WeatherRecord {
+            temperature: Temperature::from_celsius(celsius)?,
+            humidity: Humidity::from_percent(percent)?,
+            pressure: Pressure::from_mmhg(mmhg)?,
+            ..WeatherRecord::default()
+        })
+    }
+}
+
+/// When a station took a reading and its position among the station's readings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stamp {
+    /// Starts at 1 and grows by one with every reading, including suppressed ones.
+    pub sequence: u64,
+    pub timestamp: SystemTime,
+}
+
+/// Stamps the readings of one station.
+pub(crate) struct Stamper {
+    clock: Box<dyn Clock + Send>,
+    sequence: u64,
+}
+impl Stamper {
+    pub(crate) fn new() -> Self {
+        Stamper {
+            clock: Box::new(SystemClock),
+            sequence: 0,
+        }
+    }
+    pub(crate) fn set_clock<C: Clock + Send + 'static>(&mut self, clock: C) {
+        self.clock = Box::new(clock);
+    }
+    pub(crate) fn stamp(&mut self, record: WeatherRecord) -> WeatherRecord {
+        self.sequence += 1;
+        WeatherRecord {
+            stamp: Some(Stamp {
+                sequence: self.sequence,
+                timestamp: self.clock.system_time(),
+            }),
+            ..record
+        }
+    }
+}
+
+/// Largest per-field difference from the previous record still considered "no change",
+/// e.g. `TemperatureDelta::from_celsius(0.5)` for half a degree.
+/// The default, all zeros, treats only identical records as unchanged.
+/// An optional channel set to `None` is ignored; otherwise a channel present
+/// in only one of the records counts as a change.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Deadband {
+    pub temperature: TemperatureDelta,
+    pub humidity: Humidity,
+    pub pressure: Pressure,
+    pub wind_speed: Option<WindSpeed>,
+    pub wind_direction: Option<WindDirection>,
+    pub rainfall: Option<Rainfall>,
+    pub uv_index: Option<UvIndex>,
+    pub dew_point: Option<TemperatureDelta>,
+}
+impl Default for Deadband {
+    fn default() -> Self {
+        Deadband {
+            temperature: TemperatureDelta::default(),
+            humidity: Humidity::default(),
+            pressure: Pressure::default(),
+            wind_speed: Some(WindSpeed::default()),
+            wind_direction: Some(WindDirection::default()),
+            rainfall: Some(Rainfall::default()),
+            uv_index: Some(UvIndex::default()),
+            dew_point: Some(TemperatureDelta::default()),
+        }
+    }
+}
+impl Deadband {
+    /// Whether `current` differs from `previous` by more than the deadband in any field.
+    pub fn exceeded(&self, previous: &WeatherRecord, current: &WeatherRecord) -> bool {
+        current.temperature.abs_diff(&previous.temperature) > self.temperature ||
+        current.humidity.abs_diff(&previous.humidity) > self.humidity ||
+        current.pressure.abs_diff(&previous.pressure) > self.pressure ||
+        channel_exceeded(self.wind_speed, previous.wind_speed, current.wind_speed, WindSpeed::abs_diff) ||
+        channel_exceeded(self.wind_direction,
+                         previous.wind_direction,
+                         current.wind_direction,
+                         WindDirection::abs_diff) ||
+        channel_exceeded(self.rainfall, previous.rainfall, current.rainfall, Rainfall::abs_diff) ||
+        channel_exceeded(self.uv_index, previous.uv_index, current.uv_index, UvIndex::abs_diff) ||
+        channel_exceeded(self.dew_point, previous.dew_point, current.dew_point, Temperature::abs_diff)
+    }
+}
+fn channel_exceeded<T, D, F>(deadband: Option<D>, previous: Option<T>, current: Option<T>, abs_diff: F) -> bool
+    where D: PartialOrd,
+          F: Fn(&T, &T) -> D
+{
+    match (deadband, previous, current) {
+        (None, _, _) => false,
+        (Some(deadband), Some(previous), Some(current)) => abs_diff(&current, &previous) > deadband,
+        (Some(_), previous, current) => previous.is_some() != current.is_some(),
+    }
+}
+
+/// Observer notified only when the record changes beyond the deadband since
+/// the last record it received.
+pub struct Distinct<O> {
+    observer: O,
+    deadband: Deadband,
+    last: Option<WeatherRecord>,
+}
+impl<O> Distinct<O> {
+    pub fn new(observer: O, deadband: Deadband) -> Self {
+        Distinct {
+            observer,
+            deadband,
+            last: None,
+        }
+    }
+}
+impl<O: Observer<WeatherRecord>> Observer<WeatherRecord> for Distinct<O> {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        self.observer.update(record)
+    }
+    fn name(&self) -> String {
+        self.observer.name()
+    }
+    fn is_closed(&self) -> bool {
+        self.observer.is_closed()
+    }
+    fn accepts(&mut self, record: &WeatherRecord) -> bool {
+        let changed = match self.last {
+            Some(ref last) => self.deadband.exceeded(last, record),
+            None => true,
+        };
+        if changed && self.observer.accepts(record) {
+            self.last = Some(*record);
+            return true;
+        }
+        false
+    }
+}
+
+use channel::Channel;
+use sensor::{Sensor, RandomSensor};
+use stream::{self, ObserverStream};
+use rand::ChaChaRng;
+use observer::{Observer, Observable, ErrorPolicy, NotifyReport, Priority, Quarantined, Subscribers,
+               SubscriptionId, SubscriptionError, SubscriptionStats, UpdateError};
+
+pub struct WeatherData<S = RandomSensor> {
+    sensor: S,
+    stamper: Stamper,
+    deadband: Option<Deadband>,
+    last: Option<WeatherRecord>,
+    observers: Subscribers<WeatherRecord>,
+    temperature: Channel<Temperature>,
+    humidity: Channel<Humidity>,
+    pressure: Channel<Pressure>,
+}
+impl WeatherData {
+    pub fn new() -> Self {
+        WeatherData::with_sensor(RandomSensor::new())
+    }
+}
+impl WeatherData<RandomSensor<ChaChaRng>> {
+    /// Stations created with the same seed produce the same measurements.
+    pub fn seeded(seed: u64) -> Self {
+        WeatherData::with_sensor(RandomSensor::seeded(seed))
+    }
+}
+impl<S: Sensor> WeatherData<S> {
+    pub fn with_sensor(sensor: S) -> Self {
+        WeatherData {
+            sensor,
+            stamper: Stamper::new(),
+            deadband: None,
+            last: None,
+            observers: Subscribers::new(),
+            temperature: Channel::new(),
+            humidity: Channel::new(),
+            pressure: Channel::new(),
+        }
+    }
+    /// Clock timestamping the records, `SystemClock` by default.
+    pub fn set_clock<C: Clock + Send + 'static>(&mut self, clock: C) {
+        self.stamper.set_clock(clock);
+    }
+    /// Subject notifying about temperature changes only.
+    pub fn temperature(&mut self) -> &mut Channel<Temperature> {
+        &mut self.temperature
+    }
+    /// Subject notifying about humidity changes only.
+    pub fn humidity(&mut self) -> &mut Channel<Humidity> {
+        &mut self.humidity
+    }
+    /// Subject notifying about pressure changes only.
+    pub fn pressure(&mut self) -> &mut Channel<Pressure> {
+        &mut self.pressure
+    }
+    /// Subscribes a stream of records buffering up to `capacity` of them.
+    /// Dropping the stream unsubscribes it on the next notification.
+    pub fn subscribe_stream(&mut self, capacity: usize) -> ObserverStream<WeatherRecord> {
+        let (observer, stream) = stream::channel(capacity);
+        self.register(Box::new(observer));
+        stream
+    }
+    /// With a deadband set, records that do not exceed it compared to the last
+    /// notified record are suppressed for all record observers.
+    /// Channel subjects are not affected, they track every record.
+    pub fn set_deadband(&mut self, deadband: Option<Deadband>) {
+        self.deadband = deadband;
+    }
+    /// Registers an observer notified only about records exceeding the deadband
+    /// compared to the last record it received.
+    pub fn register_distinct(&mut self,
+                             observer: Box<dyn Observer<WeatherRecord>>,
+                             deadband: Deadband)
+                             -> SubscriptionId {
+        self.register(Box::new(Distinct::new(observer, deadband)))
+    }
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.observers.set_error_policy(policy);
+    }
+    pub fn stats(&self, id: SubscriptionId) -> Option<SubscriptionStats> {
+        self.observers.stats(id)
+    }
+    /// Observers taken out of service because they panicked, with the panic messages.
+    pub fn quarantined(&self) -> Vec<Quarantined> {
+        self.observers.quarantined()
+    }
+    pub fn restore(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        self.observers.restore(id)
+    }
+    /// Reads the sensor, stamps the record and notifies observers.
+    /// Returns `None` when the sensor has no more measurements.
+    pub fn measurements_changed(&mut self) -> Option<NotifyReport> {
+        let record = self.sensor.read()?;
+        let record = self.stamper.stamp(record);
+        Some(self.notify(record))
+    }
+}
+impl Default for WeatherData {
+    fn default() -> Self {
+        WeatherData::new()
+    }
+}
+impl<S: Sensor> Observable<WeatherRecord> for WeatherData<S> {
+    fn register_with_priority(&mut self,
+                              observer: Box<dyn Observer<WeatherRecord>>,
+                              priority: Priority)
+                              -> SubscriptionId {
+        self.observers.insert(observer, priority)
+    }
+    fn remove(&mut self, id: SubscriptionId) -> Result<(), SubscriptionError> {
+        self.observers.remove(id)
+    }
+    /// Notifies record observers first, then observers of the channels that changed.
+    /// The report covers both.
+    fn notify(&mut self, record: WeatherRecord) -> NotifyReport {
+        let mut report = NotifyReport::default();
+        let suppressed = match (self.deadband, self.last) {
+            (Some(deadband), Some(last)) => !deadband.exceeded(&last, &record),
+            _ => false,
+        };
+        if suppressed {
+            report.suppressed = true;
+        } else {
+            self.last = Some(record);
+            report = self.observers.notify(&record);
+        }
+        report.merge(self.temperature.notify(record.temperature));
+        report.merge(self.humidity.notify(record.humidity));
+        report.merge(self.pressure.notify(record.pressure));
+        report
+    }
+}
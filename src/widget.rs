@@ -0,0 +1,424 @@
+use std::io::{self, Stdout, Write};
+
+use clock::format_utc;
+use weather::{WeatherRecord, Stamp, Humidity, WindDirection};
+use observer::{Observer, UpdateError};
+use units::{format_value, Units};
+
+pub trait DisplayWidget {
+    fn display(&mut self) -> io::Result<()>;
+}
+
+// Unit symbol separated from the value, nothing for dimensionless values.
+fn with_symbol(symbol: &str) -> String {
+    if symbol.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", symbol)
+    }
+}
+
+// Writes when and in which order the record was taken, if known.
+fn write_stamp<W: Write>(output: &mut W, stamp: Option<Stamp>) -> io::Result<()> {
+    match stamp {
+        Some(stamp) => writeln!(output, "\tTime\t\t: {} (#{})", format_utc(stamp.timestamp), stamp.sequence),
+        None => Ok(()),
+    }
+}
+
+/// ********************* WidgetCurrent *****************************
+pub struct WidgetCurrent<W = Stdout> {
+    name: String,
+    output: W,
+    units: Units,
+    current: WeatherRecord,
+}
+impl WidgetCurrent {
+    pub fn new<Name: Into<String>>(name: Name) -> WidgetCurrent {
+        WidgetCurrent::with_output(name, io::stdout())
+    }
+}
+impl<W: Write> WidgetCurrent<W> {
+    pub fn with_output<Name: Into<String>>(name: Name, output: W) -> WidgetCurrent<W> {
+        WidgetCurrent {
+            name: name.into(),
+            output,
+            units: Units::default(),
+            current: WeatherRecord::new(),
+        }
+    }
+    pub fn set_units(&mut self, units: Units) {
+        self.units = units;
+    }
+    /// The last record received.
+    pub fn current(&self) -> &WeatherRecord {
+        &self.current
+    }
+}
+impl<W: Write> Observer<WeatherRecord> for WidgetCurrent<W> {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        self.current = *record;
+        self.display()?;
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+impl<W: Write> DisplayWidget for WidgetCurrent<W> {
+    fn display(&mut self) -> io::Result<()> {
+        let units = self.units;
+        writeln!(self.output, "{}", &self.name)?;
+        write_stamp(&mut self.output, self.current.stamp)?;
+        writeln!(self.output,
+                 "\tTemperature\t: {} {}\n\tHumid\t\t: {} {}\n\tPress\t\t: {} {}",
+                 format_value(self.current.temperature.value(units.temperature)),
+                 units.temperature.symbol(),
+                 format_value(self.current.humidity.percent()),
+                 Humidity::SYMBOL,
+                 format_value(self.current.pressure.value(units.pressure)),
+                 units.pressure.symbol())?;
+
+        let current = self.current;
+        if let Some(speed) = current.wind_speed {
+            writeln!(self.output,
+                     "\tWind\t\t: {} {}",
+                     format_value(speed.value(units.wind_speed)),
+                     units.wind_speed.symbol())?;
+        }
+        if let Some(direction) = current.wind_direction {
+            writeln!(self.output,
+                     "\tWind from\t: {}{} {}",
+                     format_value(direction.degrees()),
+                     WindDirection::SYMBOL,
+                     direction.compass())?;
+        }
+        if let Some(rainfall) = current.rainfall {
+            writeln!(self.output,
+                     "\tRainfall\t: {} {}",
+                     format_value(rainfall.value(units.rainfall)),
+                     units.rainfall.symbol())?;
+        }
+        if let Some(uv_index) = current.uv_index {
+            writeln!(self.output, "\tUV index\t: {}", format_value(uv_index.value()))?;
+        }
+        if let Some(dew_point) = current.dew_point {
+            writeln!(self.output,
+                     "\tDew point\t: {} {}",
+                     format_value(dew_point.value(units.temperature)),
+                     units.temperature.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+/// ********************* WidgetStatistic *****************************
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+use clock::{Clock, SystemClock};
+use statistics::{Quantity, RollingStatistics};
+
+/// Records a `WidgetStatistic` computes its statistics over.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HistoryWindow {
+    /// The last `n` records, at least one.
+    Count(usize),
+    /// Records taken at most this long before the newest one.
+    Span(Duration),
+}
+impl Default for HistoryWindow {
+    fn default() -> Self {
+        HistoryWindow::Count(10)
+    }
+}
+
+const QUANTITIES: [Quantity; 7] = [Quantity::Temperature,
+                                   Quantity::Humidity,
+                                   Quantity::Pressure,
+                                   Quantity::WindSpeed,
+                                   Quantity::Rainfall,
+                                   Quantity::UvIndex,
+                                   Quantity::DewPoint];
+
+// Reading of the quantity in the given units, `None` if the record lacks it.
+fn reading(record: &WeatherRecord, quantity: Quantity, units: Units) -> Option<f64> {
+    match quantity {
+        Quantity::Temperature => Some(record.temperature.value(units.temperature)),
+        Quantity::Humidity => Some(record.humidity.percent()),
+        Quantity::Pressure => Some(record.pressure.value(units.pressure)),
+        Quantity::WindSpeed => record.wind_speed.map(|speed| speed.value(units.wind_speed)),
+        Quantity::Rainfall => record.rainfall.map(|rainfall| rainfall.value(units.rainfall)),
+        Quantity::UvIndex => record.uv_index.map(|uv_index| uv_index.value()),
+        Quantity::DewPoint => record.dew_point.map(|dew_point| dew_point.value(units.temperature)),
+    }
+}
+
+/// Min, max and average of every channel over a window of the last records,
+/// the rest of the statistics are available through `WidgetStatistic::statistics`.
+/// Wind direction is left out, it cannot be averaged that way.
+///
+/// Records are timed by their stamps; records without one are timed by the
+/// widget clock when they arrive.
+pub struct WidgetStatistic<W = Stdout> {
+    name: String,
+    output: W,
+    units: Units,
+    clock: Box<dyn Clock + Send>,
+    window: HistoryWindow,
+    history: VecDeque<(SystemTime, WeatherRecord)>,
+    statistics: [RollingStatistics; 7],
+}
+impl WidgetStatistic {
+    pub fn new<Name: Into<String>>(name: Name) -> WidgetStatistic {
+        WidgetStatistic::with_output(name, io::stdout())
+    }
+}
+impl<W: Write> WidgetStatistic<W> {
+    pub fn with_output<Name: Into<String>>(name: Name, output: W) -> WidgetStatistic<W> {
+        WidgetStatistic {
+            name: name.into(),
+            output,
+            units: Units::default(),
+            clock: Box::new(SystemClock),
+            window: HistoryWindow::default(),
+            history: VecDeque::new(),
+            statistics: Default::default(),
+        }
+    }
+    /// Statistics are recomputed from the history in the new units.
+    pub fn set_units(&mut self, units: Units) {
+        self.units = units;
+        self.statistics = Default::default();
+        for &(time, ref record) in &self.history {
+            for &quantity in &QUANTITIES {
+                if let Some(value) = reading(record, quantity, units) {
+                    self.statistics[quantity as usize].push(time, value);
+                }
+            }
+        }
+    }
+    /// Clock timing records without a stamp, `SystemClock` by default.
+    pub fn set_clock<C: Clock + Send + 'static>(&mut self, clock: C) {
+        self.clock = Box::new(clock);
+    }
+    /// Takes effect immediately: records outside of the new window are dropped.
+    pub fn set_window(&mut self, window: HistoryWindow) {
+        self.window = match window {
+            HistoryWindow::Count(count) => HistoryWindow::Count(count.max(1)),
+            span => span,
+        };
+        self.strip_history();
+    }
+    pub fn window(&self) -> HistoryWindow {
+        self.window
+    }
+    /// Number of records kept in the history.
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+    /// Records in the window, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &WeatherRecord> + '_ {
+        self.history.iter().map(|(_, record)| record)
+    }
+    /// Statistics of the quantity over the window, in the units of the widget.
+    pub fn statistics(&self, quantity: Quantity) -> &RollingStatistics {
+        &self.statistics[quantity as usize]
+    }
+    fn strip_history(&mut self) {
+        match self.window {
+            HistoryWindow::Count(count) => {
+                while self.history.len() > count {
+                    self.pop_front();
+                }
+            }
+            HistoryWindow::Span(span) => {
+                let newest = match self.history.back() {
+                    Some(&(time, _)) => time,
+                    None => return,
+                };
+                // Records from the future of the newest one are kept.
+                while let Some(&(time, _)) = self.history.front() {
+                    match newest.duration_since(time) {
+                        Ok(age) if age > span => self.pop_front(),
+                        _ => break,
+                    }
+                }
+            }
+        }
+    }
+    fn push_back(&mut self, time: SystemTime, record: WeatherRecord) {
+        for &quantity in &QUANTITIES {
+            if let Some(value) = reading(&record, quantity, self.units) {
+                self.statistics[quantity as usize].push(time, value);
+            }
+        }
+        self.history.push_back((time, record));
+    }
+    fn pop_front(&mut self) {
+        if let Some((_, record)) = self.history.pop_front() {
+            for &quantity in &QUANTITIES {
+                if reading(&record, quantity, self.units).is_some() {
+                    self.statistics[quantity as usize].pop_front();
+                }
+            }
+        }
+    }
+}
+// Writes one statistic line, skipped for channels missing from the history.
+fn write_statistic<W: Write>(output: &mut W,
+                             label: &str,
+                             statistics: &RollingStatistics,
+                             symbol: &str)
+                             -> io::Result<()> {
+    match (statistics.min(), statistics.max(), statistics.mean()) {
+        (Some(min), Some(max), Some(avg)) => {
+            writeln!(output,
+                     "\t{}: {} / {} / {}{}",
+                     label,
+                     format_value(min),
+                     format_value(max),
+                     format_value(avg),
+                     with_symbol(symbol))
+        }
+        _ => Ok(()),
+    }
+}
+impl<W: Write> Observer<WeatherRecord> for WidgetStatistic<W> {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        let time = match record.stamp {
+            Some(stamp) => stamp.timestamp,
+            None => self.clock.system_time(),
+        };
+        self.push_back(time, *record);
+        self.strip_history();
+        self.display()?;
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+impl<W: Write> DisplayWidget for WidgetStatistic<W> {
+    fn display(&mut self) -> io::Result<()> {
+        let units = self.units;
+        let statistics = &self.statistics;
+        let output = &mut self.output;
+        writeln!(output, "{}", &self.name)?;
+        write_stamp(output, self.history.back().and_then(|&(_, record)| record.stamp))?;
+
+        let lines = [(Quantity::Temperature, "Temperature (min/max/avg)\t", units.temperature.symbol()),
+                     (Quantity::Humidity, "Humidity (min/max/avg) \t\t", Humidity::SYMBOL),
+                     (Quantity::Pressure, "Pressure (min/max/avg) \t\t", units.pressure.symbol()),
+                     (Quantity::WindSpeed, "Wind (min/max/avg) \t\t", units.wind_speed.symbol()),
+                     (Quantity::Rainfall, "Rainfall (min/max/avg) \t\t", units.rainfall.symbol()),
+                     (Quantity::UvIndex, "UV index (min/max/avg) \t\t", ""),
+                     (Quantity::DewPoint, "Dew point (min/max/avg)\t", units.temperature.symbol())];
+        for &(quantity, label, symbol) in &lines {
+            write_statistic(output, label, &statistics[quantity as usize], symbol)?;
+        }
+        Ok(())
+    }
+}
+
+/// ********************* WidgetForecast *****************************
+use forecast::{self, Tendency};
+use weather::Pressure;
+/// Forecast from the pressure tendency over the last `window` records, see `forecast::zambretti`.
+pub struct WidgetForecast<W = Stdout> {
+    name: String,
+    output: W,
+    units: Units,
+    window: usize,
+    threshold: Pressure,
+    pressures: VecDeque<Pressure>,
+    stamp: Option<Stamp>,
+}
+impl WidgetForecast {
+    pub fn new<Name: Into<String>>(name: Name) -> WidgetForecast {
+        WidgetForecast::with_output(name, io::stdout())
+    }
+}
+impl<W: Write> WidgetForecast<W> {
+    /// Compares the first and last of 4 records, changes up to 1.6 hPa count as steady.
+    pub fn with_output<Name: Into<String>>(name: Name, output: W) -> WidgetForecast<W> {
+        WidgetForecast {
+            name: name.into(),
+            output,
+            units: Units::default(),
+            window: 4,
+            threshold: Pressure::from_hpa(1.6).unwrap_or_default(),
+            pressures: VecDeque::new(),
+            stamp: None,
+        }
+    }
+    pub fn set_units(&mut self, units: Units) {
+        self.units = units;
+    }
+    /// Number of records the tendency is taken over, at least 2.
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(2);
+        while self.pressures.len() > self.window {
+            self.pressures.pop_front();
+        }
+    }
+    /// Largest change over the window still considered steady.
+    pub fn set_threshold(&mut self, threshold: Pressure) {
+        self.threshold = threshold;
+    }
+    /// `None` until two records arrived.
+    pub fn tendency(&self) -> Option<Tendency> {
+        match (self.pressures.front(), self.pressures.back()) {
+            (Some(&first), Some(&last)) if self.pressures.len() > 1 => {
+                Some(Tendency::of_change(first, last, self.threshold))
+            }
+            _ => None,
+        }
+    }
+    pub fn forecast(&self) -> Option<&'static str> {
+        let pressure = *self.pressures.back()?;
+        Some(forecast::zambretti(pressure, self.tendency()?))
+    }
+}
+impl<W: Write> Observer<WeatherRecord> for WidgetForecast<W> {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        if self.pressures.len() == self.window {
+            self.pressures.pop_front();
+        }
+        self.pressures.push_back(record.pressure);
+        self.stamp = record.stamp;
+        self.display()?;
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+impl<W: Write> DisplayWidget for WidgetForecast<W> {
+    fn display(&mut self) -> io::Result<()> {
+        writeln!(self.output, "{}", &self.name)?;
+        write_stamp(&mut self.output, self.stamp)?;
+        let pressure = match self.pressures.back() {
+            Some(pressure) => pressure.value(self.units.pressure),
+            None => return Ok(()),
+        };
+        match (self.tendency(), self.forecast()) {
+            (Some(tendency), Some(forecast)) => {
+                writeln!(self.output,
+                         "\tPress\t\t: {} {}, {}\n\tForecast\t: {}",
+                         format_value(pressure),
+                         self.units.pressure.symbol(),
+                         tendency,
+                         forecast)
+            }
+            _ => {
+                writeln!(self.output,
+                         "\tPress\t\t: {} {}\n\tForecast\t: not enough data",
+                         format_value(pressure),
+                         self.units.pressure.symbol())
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use observer::{panic_message, Observer, UpdateError};
+
+/// What `WorkerObserver::update` does when the queue is full.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait until the worker frees a slot.
+    Block,
+    /// Discard the oldest queued value to make room for the new one.
+    DropOldest,
+    /// Discard the new value.
+    DropNewest,
+}
+
+struct Queue<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    busy: bool,
+    closed: bool,
+    stopped: bool,
+    dropped: usize,
+    errors: Vec<UpdateError>,
+}
+
+struct Shared<T> {
+    queue: Mutex<Queue<T>>,
+    signal: Condvar,
+}
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, Queue<T>> {
+        self.queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    fn wait<'a>(&self, guard: MutexGuard<'a, Queue<T>>) -> MutexGuard<'a, Queue<T>> {
+        self.signal.wait(guard).unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Delivers updates to the wrapped observer on its own thread through a bounded queue,
+/// so a slow observer does not hold up the subject.
+///
+/// Errors returned by the wrapped observer are collected and available through
+/// `WorkerControl::take_errors`. If the wrapped observer panics the worker stops,
+/// the panic is recorded as an error and every following `update` fails.
+///
+/// Dropping the adapter (e.g. removing it from a subject) delivers the queued values
+/// and joins the worker thread.
+pub struct WorkerObserver<T> {
+    name: String,
+    overflow: Overflow,
+    shared: Arc<Shared<T>>,
+    worker: Option<JoinHandle<()>>,
+}
+impl<T: Send + 'static> WorkerObserver<T> {
+    pub fn spawn<O>(observer: O, capacity: usize, overflow: Overflow) -> Self
+        where O: Observer<T> + Send + 'static
+    {
+        assert!(capacity > 0, "WorkerObserver capacity must be positive");
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                items: VecDeque::with_capacity(capacity),
+                capacity,
+                busy: false,
+                closed: false,
+                stopped: false,
+                dropped: 0,
+                errors: Vec::new(),
+            }),
+            signal: Condvar::new(),
+        });
+        let name = observer.name();
+        let worker = {
+            let shared = shared.clone();
+            thread::spawn(move || WorkerObserver::run(observer, &shared))
+        };
+        WorkerObserver {
+            name,
+            overflow,
+            shared,
+            worker: Some(worker),
+        }
+    }
+    fn run<O: Observer<T>>(mut observer: O, shared: &Shared<T>) {
+        loop {
+            let value = {
+                let mut queue = shared.lock();
+                while queue.items.is_empty() && !queue.closed {
+                    queue = shared.wait(queue);
+                }
+                match queue.items.pop_front() {
+                    Some(value) => {
+                        queue.busy = true;
+                        shared.signal.notify_all();
+                        value
+                    }
+                    None => break,
+                }
+            };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| observer.update(&value)));
+            let mut queue = shared.lock();
+            queue.busy = false;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => queue.errors.push(error),
+                Err(payload) => {
+                    let message = panic_message(&*payload);
+                    queue.errors.push(format!("{} panicked: {}", observer.name(), message).into());
+                    queue.items.clear();
+                    break;
+                }
+            }
+            shared.signal.notify_all();
+        }
+        shared.lock().stopped = true;
+        shared.signal.notify_all();
+    }
+}
+impl<T> WorkerObserver<T> {
+    pub fn control(&self) -> WorkerControl<T> {
+        WorkerControl { shared: self.shared.clone() }
+    }
+    /// Waits until every queued value has been handled.
+    pub fn flush(&self) {
+        self.control().flush();
+    }
+    /// Delivers the queued values, stops the worker and returns the errors it collected.
+    pub fn join(mut self) -> Vec<UpdateError> {
+        self.shutdown();
+        let mut queue = self.shared.lock();
+        queue.errors.drain(..).collect()
+    }
+    fn shutdown(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.signal.notify_all();
+        if let Some(worker) = self.worker.take() {
+            // The worker catches observer panics, so it always exits normally.
+            let _ = worker.join();
+        }
+    }
+}
+impl<T> Drop for WorkerObserver<T> {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+impl<T: Clone> Observer<T> for WorkerObserver<T> {
+    fn update(&mut self, value: &T) -> Result<(), UpdateError> {
+        let mut queue = self.shared.lock();
+        loop {
+            if queue.stopped {
+                return Err(format!("worker of {} has stopped", self.name).into());
+            }
+            if queue.items.len() < queue.capacity {
+                break;
+            }
+            match self.overflow {
+                Overflow::Block => queue = self.shared.wait(queue),
+                Overflow::DropOldest => {
+                    queue.items.pop_front();
+                    queue.dropped += 1;
+                }
+                Overflow::DropNewest => {
+                    queue.dropped += 1;
+                    return Ok(());
+                }
+            }
+        }
+        queue.items.push_back(value.clone());
+        self.shared.signal.notify_all();
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Handle to inspect a `WorkerObserver` after it was handed over to a subject.
+pub struct WorkerControl<T> {
+    shared: Arc<Shared<T>>,
+}
+impl<T> Clone for WorkerControl<T> {
+    fn clone(&self) -> Self {
+        WorkerControl { shared: self.shared.clone() }
+    }
+}
+impl<T> WorkerControl<T> {
+    /// Waits until every queued value has been handled or the worker has stopped.
+    pub fn flush(&self) {
+        let mut queue = self.shared.lock();
+        while (queue.busy || !queue.items.is_empty()) && !queue.stopped {
+            queue = self.shared.wait(queue);
+        }
+    }
+    /// Number of values waiting in the queue.
+    pub fn pending(&self) -> usize {
+        self.shared.lock().items.len()
+    }
+    /// Number of values discarded by the overflow policy.
+    pub fn dropped(&self) -> usize {
+        self.shared.lock().dropped
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.shared.lock().stopped
+    }
+    /// Takes the errors reported by the wrapped observer so far.
+    pub fn take_errors(&self) -> Vec<UpdateError> {
+        self.shared.lock().errors.drain(..).collect()
+    }
+}
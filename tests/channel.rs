@@ -0,0 +1,80 @@
+extern crate pattern_observer;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pattern_observer::observer::{Observable, Observer, UpdateError};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{Humidity, Pressure, WeatherData};
+
+use common::record;
+
+fn station() -> WeatherData<ScriptedSensor> {
+    let script = vec![record(20, 50, 750),
+                      record(21, 50, 750),
+                      record(21, 55, 750),
+                      record(21, 55, 748)];
+    WeatherData::with_sensor(ScriptedSensor::new(script))
+}
+
+struct Barometer {
+    readings: Rc<RefCell<Vec<f64>>>,
+}
+impl Observer<Pressure> for Barometer {
+    fn update(&mut self, pressure: &Pressure) -> Result<(), UpdateError> {
+        self.readings.borrow_mut().push(pressure.mmhg());
+        Ok(())
+    }
+    fn name(&self) -> String {
+        "barometer".to_string()
+    }
+}
+
+#[test]
+fn channels_notify_on_change_only() {
+    let mut weather = station();
+    let temperatures = Rc::new(RefCell::new(Vec::new()));
+    let humidities = Rc::new(RefCell::new(Vec::new()));
+    let pressures = Rc::new(RefCell::new(Vec::new()));
+    {
+        let temperatures = temperatures.clone();
+        weather.temperature().register_fn(move |t| temperatures.borrow_mut().push(t.celsius()));
+        let humidities = humidities.clone();
+        weather.humidity().register_fn(move |h| humidities.borrow_mut().push(h.percent()));
+    }
+    weather.pressure().register(Box::new(Barometer { readings: pressures.clone() }));
+    while weather.measurements_changed().is_some() {}
+
+    assert_eq!(*temperatures.borrow(), vec![20.0, 21.0]);
+    assert_eq!(*humidities.borrow(), vec![50.0, 55.0]);
+    assert_eq!(*pressures.borrow(), vec![750.0, 748.0]);
+    assert_eq!(weather.pressure().last().map(Pressure::mmhg), Some(748.0));
+}
+
+#[test]
+fn report_covers_channel_observers() {
+    let mut weather = station();
+    weather.register_fn(|_| ());
+    weather.temperature().register_fn(|_| ());
+    weather.pressure().register_fn(|_| ());
+
+    assert_eq!(weather.measurements_changed().unwrap().delivered, 3);
+    assert_eq!(weather.measurements_changed().unwrap().delivered, 2);
+    assert_eq!(weather.measurements_changed().unwrap().delivered, 1);
+}
+
+#[test]
+fn channel_can_be_notified_directly() {
+    let mut weather = WeatherData::new();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let probe = seen.clone();
+    let id = weather.humidity().register_fn(move |h| probe.borrow_mut().push(h.percent()));
+    let percent = |value| Humidity::from_percent(value).unwrap();
+    assert!(!weather.humidity().notify(percent(40.0)).suppressed);
+    assert!(weather.humidity().notify(percent(40.0)).suppressed);
+    weather.humidity().remove(id).unwrap();
+    weather.humidity().notify(percent(41.0));
+    assert_eq!(*seen.borrow(), vec![40.0]);
+}
@@ -0,0 +1,63 @@
+extern crate pattern_observer;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use pattern_observer::observer::{Observable, Observer, FnObserver};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::shared::SharedWeatherData;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+
+fn station(count: i32) -> WeatherData<ScriptedSensor> {
+    let script = (0..count)
+        .map(|t| WeatherRecord::from_readings(f64::from(t), 50.0, 760.0).unwrap())
+        .collect();
+    WeatherData::with_sensor(ScriptedSensor::new(script))
+}
+
+#[test]
+fn closure_receives_records() {
+    let mut weather = station(3);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let probe = seen.clone();
+    weather.register_fn(move |record| probe.borrow_mut().push(record.temperature.celsius()));
+    while weather.measurements_changed().is_some() {}
+    assert_eq!(*seen.borrow(), vec![0.0, 1.0, 2.0]);
+}
+
+#[test]
+fn closures_get_distinct_subscriptions() {
+    let mut weather = station(2);
+    let alerts = Rc::new(Cell::new(0));
+    let first = {
+        let alerts = alerts.clone();
+        weather.register_fn(move |_| alerts.set(alerts.get() + 1))
+    };
+    let second = {
+        let alerts = alerts.clone();
+        weather.register_fn(move |_| alerts.set(alerts.get() + 10))
+    };
+    assert!(first != second);
+
+    weather.measurements_changed();
+    weather.remove(second).unwrap();
+    weather.measurements_changed();
+    assert_eq!(alerts.get(), 12);
+}
+
+#[test]
+fn named_closure_observer() {
+    let observer = FnObserver::named("alert", |_: &WeatherRecord| ());
+    assert_eq!(Observer::<WeatherRecord>::name(&observer), "alert");
+}
+
+#[test]
+fn shared_subject_accepts_closures() {
+    let weather = SharedWeatherData::seeded(5);
+    let count = Arc::new(Mutex::new(0));
+    let probe = count.clone();
+    weather.register_fn(move |_| *probe.lock().unwrap() += 1);
+    weather.measurements_changed();
+    assert_eq!(*count.lock().unwrap(), 1);
+}
@@ -0,0 +1,175 @@
+extern crate pattern_observer;
+
+mod common;
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::Duration;
+
+use pattern_observer::clock::ManualClock;
+use pattern_observer::combinator::ObservableExt;
+use pattern_observer::observer::{ErrorPolicy, Observable, Observer, UpdateError};
+use pattern_observer::sensor::Sensor;
+use pattern_observer::weather::WeatherData;
+
+use common::station;
+
+fn collect<T: Clone + Debug + 'static, S: Observable<T>>(subject: &mut S) -> Rc<RefCell<Vec<T>>> {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let probe = seen.clone();
+    subject.register_fn(move |value: &T| probe.borrow_mut().push(value.clone()));
+    seen
+}
+
+struct Failing;
+impl Observer<f64> for Failing {
+    fn update(&mut self, _: &f64) -> Result<(), UpdateError> {
+        Err("broken".into())
+    }
+    fn name(&self) -> String {
+        "failing".to_string()
+    }
+}
+
+fn run<S: Sensor>(weather: &mut WeatherData<S>) {
+    while weather.measurements_changed().is_some() {}
+}
+
+#[test]
+fn map_and_filter() {
+    let mut weather = station(&[10, 25, 18, 31]);
+    let mut temperatures = weather.map(|record| record.temperature.celsius());
+    let hot = collect(&mut temperatures.filter(|t| *t > 20.0));
+    let all = collect(&mut temperatures);
+    run(&mut weather);
+    assert_eq!(*hot.borrow(), vec![25.0, 31.0]);
+    assert_eq!(*all.borrow(), vec![10.0, 25.0, 18.0, 31.0]);
+}
+
+#[test]
+fn intermediate_handles_can_be_dropped() {
+    let mut weather = station(&[1, 2, 3]);
+    let doubled = collect(&mut weather.map(|record| record.temperature.celsius()).map(|t| t * 2.0));
+    run(&mut weather);
+    assert_eq!(*doubled.borrow(), vec![2.0, 4.0, 6.0]);
+}
+
+#[test]
+fn unused_derived_subject_is_pruned() {
+    let mut weather = station(&[1, 2]);
+    drop(weather.map(|record| record.temperature.celsius()));
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.pruned.len(), 1);
+}
+
+#[test]
+fn failures_downstream_stay_with_the_derived_subject() {
+    let mut weather = station(&[1, 2, 3, 4, 5]);
+    weather.set_error_policy(ErrorPolicy::Unsubscribe(1));
+    let mut temperatures = weather.map(|record| record.temperature.celsius());
+    temperatures.register(Box::new(Failing));
+    let healthy = collect(&mut temperatures);
+    let all = collect(&mut weather);
+
+    let report = weather.measurements_changed().unwrap();
+    assert!(report.failures.is_empty());
+    assert!(report.unsubscribed.is_empty());
+    let derived = temperatures.take_report().unwrap();
+    assert_eq!(derived.failures.len(), 1);
+    assert_eq!(derived.failures[0].name, "failing");
+    assert!(temperatures.take_report().is_none());
+
+    run(&mut weather);
+    assert_eq!(*healthy.borrow(), vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    assert_eq!(all.borrow().len(), 5);
+}
+
+#[test]
+fn derived_subject_has_its_own_error_policy() {
+    let mut weather = station(&[1, 2, 3]);
+    weather.set_error_policy(ErrorPolicy::StopOnFirst);
+    let mut temperatures = weather.map(|record| record.temperature.celsius());
+    temperatures.set_error_policy(ErrorPolicy::Unsubscribe(2));
+    let id = temperatures.register(Box::new(Failing));
+    let all = collect(&mut weather);
+
+    weather.measurements_changed();
+    assert!(temperatures.take_report().unwrap().unsubscribed.is_empty());
+    weather.measurements_changed();
+    assert_eq!(temperatures.take_report().unwrap().unsubscribed, vec![id]);
+    weather.measurements_changed();
+    assert_eq!(all.borrow().len(), 3);
+}
+
+#[test]
+fn scan_accumulates() {
+    let mut weather = station(&[1, 2, 3, 4]);
+    let sums = collect(&mut weather.scan(0.0, |sum, record| sum + record.temperature.celsius()));
+    run(&mut weather);
+    assert_eq!(*sums.borrow(), vec![1.0, 3.0, 6.0, 10.0]);
+}
+
+#[test]
+fn window_and_buffer() {
+    let mut weather = station(&[1, 2, 3, 4, 5]);
+    let mut temperatures = weather.map(|record| record.temperature.celsius());
+    let windows = collect(&mut temperatures.window(3));
+    let buffers = collect(&mut temperatures.buffer(2));
+    run(&mut weather);
+    assert_eq!(*windows.borrow(), vec![vec![1.0, 2.0, 3.0], vec![2.0, 3.0, 4.0], vec![3.0, 4.0, 5.0]]);
+    assert_eq!(*buffers.borrow(), vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+}
+
+#[test]
+fn distinct_until_changed() {
+    let mut weather = station(&[1, 1, 2, 2, 1]);
+    let distinct = collect(&mut weather.map(|record| record.temperature.celsius()).distinct_until_changed());
+    run(&mut weather);
+    assert_eq!(*distinct.borrow(), vec![1.0, 2.0, 1.0]);
+}
+
+#[test]
+fn throttle_drops_values_within_period() {
+    let clock = ManualClock::new();
+    let mut weather = station(&[1, 2, 3, 4, 5]);
+    let throttled = collect(&mut weather.map(|record| record.temperature.celsius())
+        .throttle_with_clock(Duration::from_secs(10), clock.clone()));
+    for _ in 0..5 {
+        weather.measurements_changed();
+        clock.advance(Duration::from_secs(4));
+    }
+    // Values arrive at 0, 4, 8, 12 and 16 seconds.
+    assert_eq!(*throttled.borrow(), vec![1.0, 4.0]);
+}
+
+#[test]
+fn debounce_emits_after_quiet_period() {
+    let clock = ManualClock::new();
+    let mut weather = station(&[1, 2, 3, 4]);
+    let mut debounced = weather.map(|record| record.temperature.celsius())
+        .debounce_with_clock(Duration::from_secs(10), clock.clone());
+    let seen = collect(&mut debounced);
+
+    weather.measurements_changed();
+    clock.advance(Duration::from_secs(3));
+    weather.measurements_changed();
+    clock.advance(Duration::from_secs(12));
+    weather.measurements_changed();
+    assert_eq!(*seen.borrow(), vec![2.0]);
+
+    clock.advance(Duration::from_secs(1));
+    weather.measurements_changed();
+    assert_eq!(*seen.borrow(), vec![2.0]);
+    assert!(debounced.flush().is_some());
+    assert!(debounced.flush().is_none());
+    assert_eq!(*seen.borrow(), vec![2.0, 4.0]);
+}
+
+#[test]
+fn combinators_work_on_channels() {
+    let mut weather = station(&[1, 5, 5, 9]);
+    let deltas = collect(&mut weather.temperature().window(2).map(|w| w[1].celsius() - w[0].celsius()));
+    run(&mut weather);
+    assert_eq!(*deltas.borrow(), vec![4.0, 4.0]);
+}
@@ -0,0 +1,16 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+
+pub fn record(temperature: i32, humidity: i32, pressure: i32) -> WeatherRecord {
+    WeatherRecord::from_readings(f64::from(temperature), f64::from(humidity), f64::from(pressure))
+        .unwrap()
+}
+
+/// Station replaying records of the given temperatures at 50 % and 760 mmHg.
+pub fn station(temperatures: &[i32]) -> WeatherData<ScriptedSensor> {
+    let script = temperatures.iter().map(|&t| record(t, 50, 760)).collect();
+    WeatherData::with_sensor(ScriptedSensor::new(script))
+}
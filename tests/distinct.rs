@@ -0,0 +1,155 @@
+extern crate pattern_observer;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pattern_observer::observer::{Observable, Observer, FnObserver};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{Deadband, Humidity, Pressure, Rainfall, Temperature, TemperatureDelta, UvIndex,
+                                WeatherData, WeatherRecord, WindDirection, WindSpeed};
+
+use common::record;
+
+fn station(script: Vec<WeatherRecord>) -> WeatherData<ScriptedSensor> {
+    WeatherData::with_sensor(ScriptedSensor::new(script))
+}
+
+fn recorder(seen: &Rc<RefCell<Vec<WeatherRecord>>>) -> Box<dyn Observer<WeatherRecord>> {
+    let seen = seen.clone();
+    // Stamps are dropped to compare with the script.
+    Box::new(FnObserver::new(move |record: &WeatherRecord| {
+        seen.borrow_mut().push(WeatherRecord { stamp: None, ..*record })
+    }))
+}
+
+#[test]
+fn deadband_exceeded() {
+    let deadband = Deadband {
+        temperature: TemperatureDelta::from_celsius(1.0).unwrap(),
+        humidity: Humidity::from_percent(5.0).unwrap(),
+        pressure: Pressure::from_mmhg(2.0).unwrap(),
+        ..Deadband::default()
+    };
+    let base = record(20, 50, 750);
+    assert!(!deadband.exceeded(&base, &record(21, 45, 748)));
+    assert!(deadband.exceeded(&base, &record(22, 50, 750)));
+    assert!(deadband.exceeded(&base, &record(20, 56, 750)));
+    assert!(deadband.exceeded(&base, &record(20, 50, 747)));
+    assert!(!Deadband::default().exceeded(&base, &base));
+    assert!(Deadband::default().exceeded(&base, &record(20, 50, 751)));
+    let rainy = WeatherRecord { rainfall: Some(Rainfall::from_millimeters(0.1).unwrap()), ..base };
+    assert!(deadband.exceeded(&base, &rainy));
+}
+
+#[test]
+fn deadband_in_fahrenheit_and_kelvin() {
+    let base = record(20, 50, 750);
+    let warmer = WeatherRecord { temperature: Temperature::from_celsius(20.1).unwrap(), ..base };
+    // Both are 1 °C.
+    let deltas = [TemperatureDelta::from_fahrenheit(1.8).unwrap(), TemperatureDelta::from_kelvin(1.0).unwrap()];
+    for &temperature in &deltas {
+        let deadband = Deadband { temperature, ..Deadband::default() };
+        assert!(!deadband.exceeded(&base, &warmer));
+        assert!(!deadband.exceeded(&base, &record(21, 50, 750)));
+        assert!(deadband.exceeded(&base, &record(22, 50, 750)));
+    }
+}
+
+// Record measuring every channel.
+fn all_channels(temperature: i32, wind_speed: f64, wind_direction: f64, rainfall: f64, uv_index: f64) -> WeatherRecord {
+    WeatherRecord {
+        wind_speed: Some(WindSpeed::from_meters_per_second(wind_speed).unwrap()),
+        wind_direction: Some(WindDirection::from_degrees(wind_direction).unwrap()),
+        rainfall: Some(Rainfall::from_millimeters(rainfall).unwrap()),
+        uv_index: Some(UvIndex::new(uv_index).unwrap()),
+        dew_point: Some(Temperature::from_celsius(f64::from(temperature) - 5.0).unwrap()),
+        ..record(temperature, 50, 750)
+    }
+}
+
+fn noise_deadband() -> Deadband {
+    Deadband {
+        wind_speed: Some(WindSpeed::from_meters_per_second(1.0).unwrap()),
+        wind_direction: Some(WindDirection::from_degrees(20.0).unwrap()),
+        rainfall: None,
+        uv_index: Some(UvIndex::new(0.5).unwrap()),
+        ..Deadband::default()
+    }
+}
+
+#[test]
+fn optional_channel_deadbands() {
+    let deadband = noise_deadband();
+    let base = all_channels(20, 3.0, 350.0, 0.0, 4.0);
+    assert!(!deadband.exceeded(&base, &all_channels(20, 3.5, 5.0, 1.2, 4.5)));
+    assert!(deadband.exceeded(&base, &all_channels(20, 4.5, 350.0, 0.0, 4.0)));
+    assert!(deadband.exceeded(&base, &all_channels(20, 3.0, 320.0, 0.0, 4.0)));
+    assert!(deadband.exceeded(&base, &all_channels(20, 3.0, 350.0, 0.0, 5.0)));
+    // The dew point keeps the default deadband of zero.
+    assert!(deadband.exceeded(&base, &WeatherRecord { dew_point: Some(base.temperature), ..base }));
+    // A channel appearing or disappearing is a change unless ignored.
+    assert!(deadband.exceeded(&base, &WeatherRecord { uv_index: None, ..base }));
+    assert!(!deadband.exceeded(&base, &WeatherRecord { rainfall: None, ..base }));
+}
+
+#[test]
+fn subject_deadband_suppresses_noise_in_optional_channels() {
+    let script = vec![all_channels(20, 3.0, 90.0, 0.0, 4.0),
+                      all_channels(20, 3.4, 95.0, 0.3, 4.2),
+                      all_channels(20, 2.7, 80.0, 0.1, 3.8),
+                      all_channels(20, 5.0, 90.0, 0.0, 4.0),
+                      all_channels(20, 5.2, 85.0, 0.2, 4.1)];
+    let mut weather = station(script);
+    weather.set_deadband(Some(noise_deadband()));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    weather.register(recorder(&seen));
+
+    let suppressed: Vec<bool> = (0..5).map(|_| weather.measurements_changed().unwrap().suppressed).collect();
+    assert_eq!(suppressed, vec![false, true, true, false, true]);
+}
+
+#[test]
+fn subject_suppresses_identical_records() {
+    let script = vec![record(1, 2, 3), record(1, 2, 3), record(1, 2, 4), record(1, 2, 4)];
+    let mut weather = station(script);
+    weather.set_deadband(Some(Deadband::default()));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    weather.register(recorder(&seen));
+
+    let suppressed: Vec<bool> = (0..4).map(|_| weather.measurements_changed().unwrap().suppressed).collect();
+    assert_eq!(suppressed, vec![false, true, false, true]);
+    assert_eq!(*seen.borrow(), vec![record(1, 2, 3), record(1, 2, 4)]);
+}
+
+#[test]
+fn subject_deadband_compares_with_last_notified_record() {
+    let script = (0..6).map(|t| record(t, 0, 0)).collect();
+    let mut weather = station(script);
+    weather.set_deadband(Some(Deadband {
+        temperature: TemperatureDelta::from_celsius(1.0).unwrap(),
+        ..Deadband::default()
+    }));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    weather.register(recorder(&seen));
+    while weather.measurements_changed().is_some() {}
+
+    let temperatures: Vec<f64> = seen.borrow().iter().map(|r| r.temperature.celsius()).collect();
+    assert_eq!(temperatures, vec![0.0, 2.0, 4.0]);
+}
+
+#[test]
+fn distinct_subscription() {
+    let script = vec![record(1, 2, 3), record(1, 2, 3), record(1, 3, 3), record(1, 3, 3)];
+    let mut weather = station(script);
+    let distinct = Rc::new(RefCell::new(Vec::new()));
+    let all = Rc::new(RefCell::new(Vec::new()));
+    let id = weather.register_distinct(recorder(&distinct), Deadband::default());
+    weather.register(recorder(&all));
+    while weather.measurements_changed().is_some() {}
+
+    assert_eq!(*distinct.borrow(), vec![record(1, 2, 3), record(1, 3, 3)]);
+    assert_eq!(all.borrow().len(), 4);
+    assert_eq!(weather.stats(id).unwrap().filtered, 2);
+}
@@ -0,0 +1,123 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+
+use pattern_observer::observer::{Observable, Observer, ErrorPolicy, UpdateError};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+use pattern_observer::widget::WidgetCurrent;
+
+/// Fails the updates whose (zero based) numbers are listed in `failing`.
+struct Flaky {
+    name: String,
+    failing: Vec<usize>,
+    calls: Rc<RefCell<usize>>,
+}
+impl Observer<WeatherRecord> for Flaky {
+    fn update(&mut self, _: &WeatherRecord) -> Result<(), UpdateError> {
+        let call = *self.calls.borrow();
+        *self.calls.borrow_mut() += 1;
+        if self.failing.contains(&call) {
+            Err(format!("{} failed update {}", self.name, call).into())
+        } else {
+            Ok(())
+        }
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+fn flaky(name: &str, failing: Vec<usize>) -> (Box<Flaky>, Rc<RefCell<usize>>) {
+    let calls = Rc::new(RefCell::new(0));
+    let observer = Flaky {
+        name: name.to_string(),
+        failing,
+        calls: calls.clone(),
+    };
+    (Box::new(observer), calls)
+}
+
+fn station() -> WeatherData<ScriptedSensor> {
+    WeatherData::with_sensor(ScriptedSensor::new(vec![WeatherRecord::new(); 10]))
+}
+
+#[test]
+fn continue_policy_reports_and_goes_on() {
+    let mut weather = station();
+    let (bad, _) = flaky("bad", (0..10).collect());
+    let (good, good_calls) = flaky("good", vec![]);
+    let bad_id = weather.register(bad);
+    weather.register(good);
+
+    let report = weather.measurements_changed().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.delivered, 2);
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].id, bad_id);
+    assert_eq!(report.failures[0].name, "bad");
+    assert_eq!(report.failures[0].error.to_string(), "bad failed update 0");
+    assert!(report.unsubscribed.is_empty());
+    assert_eq!(*good_calls.borrow(), 1);
+}
+
+#[test]
+fn stop_on_first_skips_remaining_observers() {
+    let mut weather = station();
+    weather.set_error_policy(ErrorPolicy::StopOnFirst);
+    let (bad, _) = flaky("bad", vec![0]);
+    let (good, good_calls) = flaky("good", vec![]);
+    weather.register(bad);
+    weather.register(good);
+
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.delivered, 1);
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(*good_calls.borrow(), 0);
+
+    let report = weather.measurements_changed().unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.delivered, 2);
+    assert_eq!(*good_calls.borrow(), 1);
+}
+
+#[test]
+fn unsubscribe_after_consecutive_failures() {
+    let mut weather = station();
+    weather.set_error_policy(ErrorPolicy::Unsubscribe(2));
+    // Failures 0 and 2 are not consecutive, failures 4 and 5 are.
+    let (bad, bad_calls) = flaky("bad", vec![0, 2, 4, 5]);
+    let bad_id = weather.register(bad);
+
+    for _ in 0..5 {
+        assert!(weather.measurements_changed().unwrap().unsubscribed.is_empty());
+    }
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.unsubscribed, vec![bad_id]);
+    assert!(weather.remove(bad_id).is_err());
+
+    weather.measurements_changed();
+    assert_eq!(*bad_calls.borrow(), 6);
+}
+
+struct BrokenPipe;
+impl io::Write for BrokenPipe {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "display disconnected"))
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn widget_write_errors_are_reported() {
+    let mut weather = station();
+    weather.register(Box::new(WidgetCurrent::with_output("Current Widget", BrokenPipe)));
+
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].name, "Current Widget");
+    assert_eq!(report.failures[0].error.to_string(), "display disconnected");
+}
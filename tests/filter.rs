@@ -0,0 +1,74 @@
+extern crate pattern_observer;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pattern_observer::observer::{Observable, Observer, FnObserver, SubscriptionStats};
+use pattern_observer::shared::SharedWeatherData;
+use pattern_observer::weather::WeatherRecord;
+
+use common::station;
+
+fn recorder(seen: &Rc<RefCell<Vec<f64>>>) -> Box<dyn Observer<WeatherRecord>> {
+    let seen = seen.clone();
+    Box::new(FnObserver::new(move |record: &WeatherRecord| seen.borrow_mut().push(record.temperature.celsius())))
+}
+
+#[test]
+fn only_matching_records_are_delivered() {
+    let mut weather = station(&[10, 25, 18, 31, 30]);
+    let hot = Rc::new(RefCell::new(Vec::new()));
+    let all = Rc::new(RefCell::new(Vec::new()));
+    let hot_id = weather.register_filtered(recorder(&hot), |record| record.temperature.celsius() >= 25.0);
+    let all_id = weather.register(recorder(&all));
+
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.delivered, 1);
+    assert_eq!(report.filtered, 1);
+    while weather.measurements_changed().is_some() {}
+
+    assert_eq!(*hot.borrow(), vec![25.0, 31.0, 30.0]);
+    assert_eq!(all.borrow().len(), 5);
+    assert_eq!(weather.stats(hot_id),
+               Some(SubscriptionStats {
+                   delivered: 3,
+                   filtered: 2,
+                   failures: 0,
+               }));
+    assert_eq!(weather.stats(all_id).unwrap().filtered, 0);
+}
+
+#[test]
+fn stats_of_removed_subscription_are_gone() {
+    let mut weather = station(&[1]);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let id = weather.register_filtered(recorder(&seen), |_| false);
+    weather.measurements_changed();
+    assert_eq!(weather.stats(id).unwrap().filtered, 1);
+    weather.remove(id).unwrap();
+    assert_eq!(weather.stats(id), None);
+}
+
+#[test]
+fn predicate_panic_quarantines_subscription() {
+    let mut weather = station(&[1, 2]);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let id = weather.register_filtered(recorder(&seen), |_| panic!("bad predicate"));
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.quarantined[0].id, id);
+    assert_eq!(report.quarantined[0].message, "bad predicate");
+    assert!(seen.borrow().is_empty());
+}
+
+#[test]
+fn shared_subject_filters() {
+    let weather = SharedWeatherData::seeded(11);
+    let id = weather.register_filtered(Box::new(FnObserver::new(|_: &WeatherRecord| ())),
+                                       |record| record.pressure.mmhg() > 1000.0);
+    for _ in 0..5 {
+        weather.measurements_changed();
+    }
+    assert_eq!(weather.stats(id).unwrap().filtered, 5);
+}
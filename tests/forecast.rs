@@ -0,0 +1,139 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use pattern_observer::clock::ManualClock;
+use pattern_observer::forecast::{zambretti, Tendency};
+use pattern_observer::observer::{Observable, Observer};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::units::Units;
+use pattern_observer::weather::{Pressure, WeatherData, WeatherRecord};
+use pattern_observer::widget::WidgetForecast;
+
+#[derive(Clone, Default)]
+struct Screen(Rc<RefCell<Vec<u8>>>);
+impl Screen {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn hpa(value: f64) -> Pressure {
+    Pressure::from_hpa(value).unwrap()
+}
+
+fn record(pressure: f64) -> WeatherRecord {
+    WeatherRecord { pressure: hpa(pressure), ..WeatherRecord::from_readings(15.0, 50.0, 760.0).unwrap() }
+}
+
+// Feeds the pressures in hPa to a fresh widget.
+fn forecast(pressures: &[f64]) -> WidgetForecast<io::Sink> {
+    let mut widget = WidgetForecast::with_output("Forecast", io::sink());
+    for &pressure in pressures {
+        widget.update(&record(pressure)).unwrap();
+    }
+    widget
+}
+
+#[test]
+fn tendency_of_change() {
+    let threshold = hpa(1.6);
+    assert_eq!(Tendency::of_change(hpa(1010.0), hpa(1011.6), threshold), Tendency::Steady);
+    assert_eq!(Tendency::of_change(hpa(1010.0), hpa(1008.4), threshold), Tendency::Steady);
+    assert_eq!(Tendency::of_change(hpa(1010.0), hpa(1011.7), threshold), Tendency::Rising);
+    assert_eq!(Tendency::of_change(hpa(1010.0), hpa(1008.3), threshold), Tendency::Falling);
+}
+
+#[test]
+fn zambretti_rules() {
+    assert_eq!(zambretti(hpa(1006.0), Tendency::Rising), "Fairly fine, possibly showers early");
+    assert_eq!(zambretti(hpa(1012.0), Tendency::Falling), "Unsettled, rain later");
+    assert_eq!(zambretti(hpa(1013.0), Tendency::Steady), "Fine, possibly showers");
+    // Out of range pressures stay within the forecasts of their tendency.
+    assert_eq!(zambretti(hpa(1050.0), Tendency::Steady), "Settled fine");
+    assert_eq!(zambretti(hpa(950.0), Tendency::Falling), "Very unsettled, rain");
+    assert_eq!(zambretti(hpa(940.0), Tendency::Rising), "Stormy, much rain");
+}
+
+#[test]
+fn forecast_follows_tendency() {
+    let rising = forecast(&[1000.0, 1002.0, 1004.0, 1006.0]);
+    assert_eq!(rising.tendency(), Some(Tendency::Rising));
+    assert_eq!(rising.forecast(), Some("Fairly fine, possibly showers early"));
+
+    let falling = forecast(&[1020.0, 1018.0, 1015.0, 1012.0]);
+    assert_eq!(falling.tendency(), Some(Tendency::Falling));
+    assert_eq!(falling.forecast(), Some("Unsettled, rain later"));
+
+    let steady = forecast(&[1013.0, 1013.5, 1012.8, 1013.0]);
+    assert_eq!(steady.tendency(), Some(Tendency::Steady));
+    assert_eq!(steady.forecast(), Some("Fine, possibly showers"));
+}
+
+#[test]
+fn tendency_needs_two_records() {
+    assert_eq!(forecast(&[]).tendency(), None);
+    assert_eq!(forecast(&[1013.0]).forecast(), None);
+    assert!(forecast(&[1013.0, 1013.0]).forecast().is_some());
+}
+
+#[test]
+fn only_the_window_counts() {
+    // A fall followed by four steady readings.
+    let widget = forecast(&[1020.0, 1010.0, 1010.0, 1010.5, 1010.0]);
+    assert_eq!(widget.tendency(), Some(Tendency::Steady));
+
+    // Growing the window does not bring back dropped records.
+    let mut widget = forecast(&[1020.0, 1010.0, 1010.0, 1010.5, 1010.0]);
+    widget.set_window(5);
+    assert_eq!(widget.tendency(), Some(Tendency::Steady));
+    widget.update(&record(1010.0)).unwrap();
+    assert_eq!(widget.tendency(), Some(Tendency::Steady));
+
+    let mut widget = WidgetForecast::with_output("Forecast", io::sink());
+    widget.set_window(5);
+    for &pressure in &[1020.0, 1010.0, 1010.0, 1010.5, 1010.0] {
+        widget.update(&record(pressure)).unwrap();
+    }
+    assert_eq!(widget.tendency(), Some(Tendency::Falling));
+    widget.set_window(2);
+    assert_eq!(widget.tendency(), Some(Tendency::Steady));
+}
+
+#[test]
+fn threshold_is_configurable() {
+    let mut widget = forecast(&[1013.0, 1014.0]);
+    assert_eq!(widget.tendency(), Some(Tendency::Steady));
+    widget.set_threshold(hpa(0.5));
+    assert_eq!(widget.tendency(), Some(Tendency::Rising));
+}
+
+#[test]
+fn forecast_widget_output() {
+    let screen = Screen::default();
+    let mut widget = WidgetForecast::with_output("Forecast", screen.clone());
+    widget.set_units(Units::metric());
+    let script = vec![record(1020.0), record(1016.0), record(1012.0)];
+    let mut weather = WeatherData::with_sensor(ScriptedSensor::new(script));
+    weather.set_clock(ManualClock::new());
+    weather.register(Box::new(widget));
+    while weather.measurements_changed().is_some() {}
+
+    assert_eq!(screen.text(),
+               "Forecast\n\tTime\t\t: 1970-01-01 00:00:00 (#1)\n\tPress\t\t: 1020 hPa\n\tForecast\t: not enough data\n\
+                Forecast\n\tTime\t\t: 1970-01-01 00:00:00 (#2)\n\tPress\t\t: 1016 hPa, falling\n\tForecast\t: \
+                Showery, becoming more unsettled\n\
+                Forecast\n\tTime\t\t: 1970-01-01 00:00:00 (#3)\n\tPress\t\t: 1012 hPa, falling\n\tForecast\t: \
+                Unsettled, rain later\n");
+}
@@ -0,0 +1,91 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pattern_observer::combinator::ObservableExt;
+use pattern_observer::metrics::{self, absolute_humidity, dew_point, heat_index, humidex, wind_chill, Metrics};
+use pattern_observer::observer::Observable;
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{Humidity, Temperature, WeatherData, WeatherRecord, WindSpeed};
+
+fn celsius(value: f64) -> Temperature {
+    Temperature::from_celsius(value).unwrap()
+}
+
+fn percent(value: f64) -> Humidity {
+    Humidity::from_percent(value).unwrap()
+}
+
+fn assert_near(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 0.05, "{} is not close to {}", actual, expected);
+}
+
+#[test]
+fn dew_point_by_magnus_formula() {
+    assert_near(dew_point(celsius(20.0), percent(50.0)).unwrap().celsius(), 9.26);
+    assert_near(dew_point(celsius(25.0), percent(100.0)).unwrap().celsius(), 25.0);
+    assert_eq!(dew_point(celsius(25.0), percent(0.0)), None);
+}
+
+#[test]
+fn heat_index_follows_weather_service_tables() {
+    let fahrenheit = |value| Temperature::from_fahrenheit(value).unwrap();
+    assert_near(heat_index(fahrenheit(90.0), percent(70.0)).fahrenheit(), 105.92);
+    assert_near(heat_index(fahrenheit(70.0), percent(50.0)).fahrenheit(), 69.05);
+    // Low and high humidity adjustments.
+    assert_near(heat_index(fahrenheit(100.0), percent(10.0)).fahrenheit(), 94.12);
+    assert_near(heat_index(fahrenheit(85.0), percent(90.0)).fahrenheit(), 101.78);
+}
+
+#[test]
+fn humidex_and_absolute_humidity() {
+    assert_near(humidex(celsius(30.0), celsius(15.0)), 33.97);
+    assert_near(absolute_humidity(celsius(20.0), percent(50.0)), 8.64);
+    assert_eq!(absolute_humidity(celsius(20.0), percent(0.0)), 0.0);
+}
+
+#[test]
+fn wind_chill_within_its_range() {
+    let wind = |value| WindSpeed::from_meters_per_second(value).unwrap();
+    // 30 km/h at -10 °C.
+    assert_near(wind_chill(celsius(-10.0), wind(30.0 / 3.6)).unwrap().celsius(), -19.52);
+    assert_eq!(wind_chill(celsius(15.0), wind(10.0)), None);
+    assert_eq!(wind_chill(celsius(-10.0), wind(1.0)), None);
+}
+
+#[test]
+fn metrics_of_record() {
+    let record = WeatherRecord {
+        wind_speed: Some(WindSpeed::from_meters_per_second(5.0).unwrap()),
+        ..WeatherRecord::from_readings(0.0, 80.0, 760.0).unwrap()
+    };
+    let metrics = Metrics::from_record(&record);
+    assert_near(metrics.dew_point.unwrap().celsius(), -3.0);
+    assert_near(metrics.wind_chill.unwrap().celsius(), -4.94);
+    assert!(metrics.humidex.is_some());
+
+    let measured = WeatherRecord { dew_point: Some(celsius(-2.0)), ..record };
+    assert_eq!(Metrics::from_record(&measured).dew_point, Some(celsius(-2.0)));
+    assert_eq!(Metrics::from_record(&WeatherRecord::from_readings(0.0, 80.0, 760.0).unwrap()).wind_chill,
+               None);
+}
+
+#[test]
+fn derived_metrics_observable() {
+    let script = vec![WeatherRecord::from_readings(20.0, 50.0, 760.0).unwrap(),
+                      WeatherRecord::from_readings(30.0, 50.0, 760.0).unwrap()];
+    let mut weather = WeatherData::with_sensor(ScriptedSensor::new(script));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    {
+        let seen = seen.clone();
+        let mut absolute = metrics::derive(&mut weather).map(|metrics| metrics.absolute_humidity);
+        absolute.register_fn(move |value: &f64| seen.borrow_mut().push(*value));
+    }
+    while weather.measurements_changed().is_some() {}
+
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 2);
+    assert_near(seen[0], 8.64);
+    assert_near(seen[1], 15.15);
+}
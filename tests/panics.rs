@@ -0,0 +1,84 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pattern_observer::observer::{Observable, Observer, UpdateError};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+
+/// Panics on the update with the given (zero based) number.
+struct Fragile {
+    name: String,
+    panic_on: usize,
+    calls: Rc<RefCell<usize>>,
+}
+impl Observer<WeatherRecord> for Fragile {
+    fn update(&mut self, _: &WeatherRecord) -> Result<(), UpdateError> {
+        let call = *self.calls.borrow();
+        *self.calls.borrow_mut() += 1;
+        if call == self.panic_on {
+            panic!("{} broke on update {}", self.name, call);
+        }
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+fn fragile(name: &str, panic_on: usize) -> (Box<Fragile>, Rc<RefCell<usize>>) {
+    let calls = Rc::new(RefCell::new(0));
+    let observer = Fragile {
+        name: name.to_string(),
+        panic_on,
+        calls: calls.clone(),
+    };
+    (Box::new(observer), calls)
+}
+
+fn station() -> WeatherData<ScriptedSensor> {
+    WeatherData::with_sensor(ScriptedSensor::new(vec![WeatherRecord::new(); 10]))
+}
+
+#[test]
+fn panicking_observer_is_quarantined() {
+    let mut weather = station();
+    let (bad, bad_calls) = fragile("bad", 1);
+    let (good, good_calls) = fragile("good", usize::MAX);
+    let bad_id = weather.register(bad);
+    weather.register(good);
+
+    assert!(weather.measurements_changed().unwrap().is_ok());
+    let report = weather.measurements_changed().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.quarantined.len(), 1);
+    assert_eq!(report.quarantined[0].id, bad_id);
+    assert_eq!(report.quarantined[0].name, "bad");
+    assert_eq!(report.quarantined[0].message, "bad broke on update 1");
+    assert_eq!(weather.quarantined(), report.quarantined);
+    assert_eq!(*good_calls.borrow(), 2);
+
+    weather.measurements_changed();
+    assert_eq!(*bad_calls.borrow(), 2);
+    assert_eq!(*good_calls.borrow(), 3);
+}
+
+#[test]
+fn quarantined_observer_can_be_restored_or_removed() {
+    let mut weather = station();
+    let (bad, bad_calls) = fragile("bad", 0);
+    let bad_id = weather.register(bad);
+    weather.measurements_changed();
+
+    weather.restore(bad_id).unwrap();
+    assert!(weather.quarantined().is_empty());
+    assert!(weather.restore(bad_id).is_err());
+    assert!(weather.measurements_changed().unwrap().is_ok());
+    assert_eq!(*bad_calls.borrow(), 2);
+
+    let (other, _) = fragile("other", 0);
+    let other_id = weather.register(other);
+    weather.measurements_changed();
+    weather.remove(other_id).unwrap();
+    assert!(weather.quarantined().is_empty());
+}
@@ -0,0 +1,156 @@
+extern crate pattern_observer;
+
+mod common;
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::rc::Rc;
+
+use pattern_observer::data::DataGen;
+use pattern_observer::observer::{Observable, Observer, UpdateError};
+use pattern_observer::sensor::{Sensor, RandomSensor, ScriptedSensor, ReplaySensor};
+use pattern_observer::weather::{Rainfall, Temperature, UvIndex, WeatherData, WeatherRecord, WindDirection, WindSpeed};
+
+use common::record;
+
+struct Probe {
+    records: Rc<RefCell<Vec<WeatherRecord>>>,
+}
+impl Observer<WeatherRecord> for Probe {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        // Stamps are dropped to compare with what the sensor read.
+        self.records.borrow_mut().push(WeatherRecord { stamp: None, ..*record });
+        Ok(())
+    }
+    fn name(&self) -> String {
+        "probe".to_string()
+    }
+}
+
+#[test]
+fn random_sensor_never_runs_out() {
+    let mut sensor = RandomSensor::new();
+    for _ in 0..100 {
+        assert!(sensor.read().is_some());
+    }
+}
+
+#[test]
+fn random_sensor_reports_values_out_of_range() {
+    let mut sensor = RandomSensor::with_generators(DataGen::seeded(10.0, 10.0, 1),
+                                                   DataGen::seeded(90.0, 20.0, 2),
+                                                   DataGen::seeded(700.0, 90.0, 3));
+    let mut readings = 0;
+    while sensor.read().is_some() {
+        readings += 1;
+        assert!(readings < 1000, "humidity above 100 % must stop the readings");
+    }
+    assert!(sensor.read().is_none());
+    let error = sensor.error().expect("error must be reported");
+    assert_eq!(error.quantity, "humidity");
+}
+
+#[test]
+fn random_sensor_reports_optional_channels_out_of_range() {
+    let mut sensor = RandomSensor::seeded(4);
+    sensor.set_uv_index(DataGen::seeded(-5.0, 1.0, 5));
+    assert!(sensor.read().is_none());
+    assert_eq!(sensor.error().unwrap().quantity, "UV index");
+}
+
+#[test]
+fn scripted_sensor_drives_weather_data() {
+    let script = vec![record(1, 2, 3), record(4, 5, 6)];
+    let mut weather = WeatherData::with_sensor(ScriptedSensor::new(script.clone()));
+    let records = Rc::new(RefCell::new(Vec::new()));
+    weather.register(Box::new(Probe { records: records.clone() }));
+
+    assert!(weather.measurements_changed().is_some());
+    assert!(weather.measurements_changed().is_some());
+    assert!(weather.measurements_changed().is_none());
+    assert_eq!(*records.borrow(), script);
+}
+
+#[test]
+fn replay_sensor_parses_lines() {
+    let text = "# temperature, humidity, pressure\n12,45,760\n\n  -3 , 80 , 741 \n";
+    let mut sensor = ReplaySensor::new(Cursor::new(text));
+    assert_eq!(sensor.read(), Some(record(12, 45, 760)));
+    assert_eq!(sensor.read(), Some(record(-3, 80, 741)));
+    assert_eq!(sensor.read(), None);
+    assert!(sensor.error().is_none());
+}
+
+#[test]
+fn replay_sensor_stops_on_malformed_line() {
+    let mut sensor = ReplaySensor::new(Cursor::new("1,2,3\n4,5\n7,8,9\n"));
+    assert_eq!(sensor.read(), Some(record(1, 2, 3)));
+    assert_eq!(sensor.read(), None);
+    assert_eq!(sensor.read(), None);
+    let error = sensor.error().expect("error must be reported");
+    assert!(error.to_string().starts_with("line 2:"));
+}
+
+#[test]
+fn replay_sensor_reads_file() {
+    let path = env::temp_dir().join(format!("pattern_observer_replay_{}.csv", std::process::id()));
+    fs::write(&path, "20,50,750\n21,51,751\n").unwrap();
+
+    let mut weather = WeatherData::with_sensor(ReplaySensor::open(&path).unwrap());
+    let records = Rc::new(RefCell::new(Vec::new()));
+    weather.register(Box::new(Probe { records: records.clone() }));
+    while weather.measurements_changed().is_some() {}
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(*records.borrow(), vec![record(20, 50, 750), record(21, 51, 751)]);
+}
+
+#[test]
+fn random_sensor_measures_optional_channels_on_request() {
+    let record = RandomSensor::seeded(3).read().unwrap();
+    assert_eq!(record.wind_speed, None);
+    assert_eq!(record.dew_point, None);
+
+    let mut sensor = RandomSensor::seeded_all_channels(3);
+    for _ in 0..50 {
+        let record = sensor.read().unwrap();
+        assert!((0.0..15.0).contains(&record.wind_speed.unwrap().meters_per_second()));
+        assert!((0.0..360.0).contains(&record.wind_direction.unwrap().degrees()));
+        assert!((0.0..2.0).contains(&record.rainfall.unwrap().millimeters()));
+        assert!((0.0..11.0).contains(&record.uv_index.unwrap().value()));
+        assert!(record.dew_point.unwrap() <= record.temperature);
+    }
+}
+
+#[test]
+fn replay_sensor_parses_optional_channels() {
+    let text = "21.4,45,760,wind_speed=3.5, wind_direction=270,rainfall=0.2,uv_index=4,dew_point=8.9\n\
+                20,50,750,uv_index=1\n";
+    let mut sensor = ReplaySensor::new(Cursor::new(text));
+    let expected = WeatherRecord {
+        wind_speed: Some(WindSpeed::from_meters_per_second(3.5).unwrap()),
+        wind_direction: Some(WindDirection::from_degrees(270.0).unwrap()),
+        rainfall: Some(Rainfall::from_millimeters(0.2).unwrap()),
+        uv_index: Some(UvIndex::new(4.0).unwrap()),
+        dew_point: Some(Temperature::from_celsius(8.9).unwrap()),
+        ..WeatherRecord::from_readings(21.4, 45.0, 760.0).unwrap()
+    };
+    assert_eq!(sensor.read(), Some(expected));
+    assert_eq!(sensor.read(),
+               Some(WeatherRecord { uv_index: Some(UvIndex::new(1.0).unwrap()), ..record(20, 50, 750) }));
+    assert_eq!(sensor.read(), None);
+    assert!(sensor.error().is_none());
+}
+
+#[test]
+fn replay_sensor_rejects_unknown_channel() {
+    let mut sensor = ReplaySensor::new(Cursor::new("1,2,3,visibility=10\n"));
+    assert_eq!(sensor.read(), None);
+    assert_eq!(sensor.error().unwrap().to_string(), "line 1: unknown channel 'visibility'");
+
+    let mut sensor = ReplaySensor::new(Cursor::new("1,2,3,wind_direction=400\n"));
+    assert_eq!(sensor.read(), None);
+    assert_eq!(sensor.error().unwrap().to_string(), "line 1: wind direction 400 ° is out of range");
+}
@@ -0,0 +1,130 @@
+extern crate pattern_observer;
+
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use pattern_observer::observer::{Observer, UpdateError};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::shared::SharedWeatherData;
+use pattern_observer::weather::WeatherRecord;
+use pattern_observer::widget::WidgetStatistic;
+
+struct Probe {
+    records: Arc<Mutex<Vec<WeatherRecord>>>,
+}
+impl Observer<WeatherRecord> for Probe {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        self.records.lock().unwrap().push(*record);
+        Ok(())
+    }
+    fn name(&self) -> String {
+        "probe".to_string()
+    }
+}
+fn probe() -> (Box<Probe>, Arc<Mutex<Vec<WeatherRecord>>>) {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    (Box::new(Probe { records: records.clone() }), records)
+}
+
+fn record(temperature: i32) -> WeatherRecord {
+    WeatherRecord::from_readings(f64::from(temperature), 50.0, 750.0).unwrap()
+}
+
+// Records without their stamps.
+fn readings(records: &Mutex<Vec<WeatherRecord>>) -> Vec<WeatherRecord> {
+    records.lock().unwrap().iter().map(|record| WeatherRecord { stamp: None, ..*record }).collect()
+}
+
+#[test]
+fn shared_weather_data_is_send_and_sync() {
+    fn check<T: Send + Sync>() {}
+    check::<SharedWeatherData>();
+    check::<SharedWeatherData<ScriptedSensor>>();
+}
+
+#[test]
+fn widgets_can_be_shared() {
+    let weather = SharedWeatherData::seeded(3);
+    weather.register(Box::new(WidgetStatistic::with_output("Statistic", io::sink())));
+    assert!(weather.measurements_changed().is_some());
+}
+
+#[test]
+fn register_from_another_thread() {
+    let weather = Arc::new(SharedWeatherData::seeded(1));
+    let (observer, records) = probe();
+
+    let id = {
+        let weather = weather.clone();
+        thread::spawn(move || weather.register(observer)).join().unwrap()
+    };
+    {
+        let weather = weather.clone();
+        thread::spawn(move || {
+                for _ in 0..5 {
+                    weather.measurements_changed();
+                }
+            })
+            .join()
+            .unwrap();
+    }
+    assert_eq!(records.lock().unwrap().len(), 5);
+
+    weather.remove(id).unwrap();
+    weather.measurements_changed();
+    assert_eq!(records.lock().unwrap().len(), 5);
+}
+
+#[test]
+fn concurrent_register_and_notify() {
+    let script = (0..1000).map(record).collect();
+    let weather = Arc::new(SharedWeatherData::with_sensor(ScriptedSensor::new(script)));
+    let (first, first_records) = probe();
+    weather.register(first);
+
+    let sensor_thread = {
+        let weather = weather.clone();
+        thread::spawn(move || while weather.measurements_changed().is_some() {})
+    };
+    let mut late = Vec::new();
+    for _ in 0..10 {
+        let (observer, records) = probe();
+        weather.register(observer);
+        late.push(records);
+    }
+    sensor_thread.join().unwrap();
+
+    let expected: Vec<_> = (0..1000).map(record).collect();
+    assert_eq!(readings(&first_records), expected);
+    // Late subscribers see a gap-free tail of the sequence.
+    for records in late {
+        let records = readings(&records);
+        assert_eq!(records, expected[expected.len() - records.len()..].to_vec());
+    }
+}
+
+#[test]
+fn records_arrive_in_sequence_order() {
+    let weather = Arc::new(SharedWeatherData::seeded(2));
+    let (observer, records) = probe();
+    weather.register(observer);
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let weather = weather.clone();
+            thread::spawn(move || for _ in 0..50 {
+                weather.measurements_changed();
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let sequences: Vec<u64> = records.lock()
+        .unwrap()
+        .iter()
+        .map(|record| record.stamp.unwrap().sequence)
+        .collect();
+    assert_eq!(sequences, (1..201).collect::<Vec<_>>());
+}
@@ -0,0 +1,77 @@
+extern crate pattern_observer;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pattern_observer::combinator::{combine_latest, merge, zip, Tagged};
+use pattern_observer::observer::Observable;
+use pattern_observer::weather::WeatherRecord;
+
+use common::station;
+
+fn temperatures(snapshot: &[Tagged<&'static str, WeatherRecord>]) -> Vec<(&'static str, f64)> {
+    snapshot.iter().map(|tagged| (tagged.source, tagged.value.temperature.celsius())).collect()
+}
+
+#[test]
+fn merge_tags_records_with_station() {
+    let mut north = station(&[1, 2]);
+    let mut south = station(&[10]);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    {
+        let mut merged = merge(vec![("north", &mut north as &mut dyn Observable<WeatherRecord>),
+                                    ("south", &mut south)]);
+        let seen = seen.clone();
+        merged.register_fn(move |tagged: &Tagged<&str, WeatherRecord>| {
+            seen.borrow_mut().push((tagged.source, tagged.value.temperature.celsius()))
+        });
+    }
+    north.measurements_changed();
+    south.measurements_changed();
+    north.measurements_changed();
+    assert_eq!(*seen.borrow(), vec![("north", 1.0), ("south", 10.0), ("north", 2.0)]);
+}
+
+#[test]
+fn zip_pairs_records_by_order() {
+    let mut north = station(&[1, 2, 3]);
+    let mut south = station(&[10, 20]);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    {
+        let mut zipped = zip(vec![("north", &mut north as &mut dyn Observable<WeatherRecord>),
+                                  ("south", &mut south)]);
+        let seen = seen.clone();
+        zipped.register_fn(move |snapshot: &Vec<Tagged<&'static str, WeatherRecord>>| {
+            seen.borrow_mut().push(temperatures(snapshot))
+        });
+    }
+    while north.measurements_changed().is_some() {}
+    assert!(seen.borrow().is_empty());
+    while south.measurements_changed().is_some() {}
+    assert_eq!(*seen.borrow(),
+               vec![vec![("north", 1.0), ("south", 10.0)], vec![("north", 2.0), ("south", 20.0)]]);
+}
+
+#[test]
+fn combine_latest_emits_snapshots() {
+    let mut north = station(&[1, 2]);
+    let mut south = station(&[10, 20]);
+    let differences = Rc::new(RefCell::new(Vec::new()));
+    {
+        let mut latest = combine_latest(vec![(0, &mut north as &mut dyn Observable<WeatherRecord>),
+                                             (1, &mut south)]);
+        let differences = differences.clone();
+        // A comparison widget attached to the derived subject.
+        latest.register_fn(move |snapshot: &Vec<Tagged<i32, WeatherRecord>>| {
+            differences.borrow_mut().push(snapshot[1].value.temperature.celsius() - snapshot[0].value.temperature.celsius())
+        });
+    }
+    north.measurements_changed();
+    assert!(differences.borrow().is_empty());
+    south.measurements_changed();
+    north.measurements_changed();
+    south.measurements_changed();
+    assert_eq!(*differences.borrow(), vec![9.0, 8.0, 18.0]);
+}
@@ -0,0 +1,248 @@
+extern crate pattern_observer;
+
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use pattern_observer::clock::ManualClock;
+use pattern_observer::observer::Observer;
+use pattern_observer::statistics::{Quantity, RollingStatistics};
+use pattern_observer::units::Units;
+use pattern_observer::weather::{Stamp, WeatherRecord};
+use pattern_observer::widget::{HistoryWindow, WidgetStatistic};
+
+fn record(temperature: f64) -> WeatherRecord {
+    WeatherRecord::from_readings(temperature, 50.0, 760.0).unwrap()
+}
+
+fn temperatures(widget: &WidgetStatistic<io::Sink>) -> Vec<f64> {
+    widget.history().map(|record| record.temperature.celsius()).collect()
+}
+
+#[test]
+fn default_window_keeps_ten_records() {
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    assert_eq!(widget.window(), HistoryWindow::Count(10));
+    for i in 0..9 {
+        widget.update(&record(f64::from(i))).unwrap();
+    }
+    assert_eq!(widget.len(), 9);
+    widget.update(&record(9.0)).unwrap();
+    assert_eq!(widget.len(), 10);
+    widget.update(&record(10.0)).unwrap();
+    assert_eq!(temperatures(&widget), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+}
+
+#[test]
+fn count_window_keeps_exactly_the_last_records() {
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    widget.set_window(HistoryWindow::Count(3));
+    for i in 0..5 {
+        widget.update(&record(f64::from(i))).unwrap();
+    }
+    assert_eq!(temperatures(&widget), [2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn count_window_keeps_at_least_one_record() {
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    widget.set_window(HistoryWindow::Count(0));
+    assert_eq!(widget.window(), HistoryWindow::Count(1));
+    widget.update(&record(1.0)).unwrap();
+    widget.update(&record(2.0)).unwrap();
+    assert_eq!(temperatures(&widget), [2.0]);
+}
+
+#[test]
+fn huge_count_window_allocates_nothing_up_front() {
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    widget.set_window(HistoryWindow::Count(usize::MAX));
+    widget.update(&record(1.0)).unwrap();
+    assert_eq!(temperatures(&widget), [1.0]);
+}
+
+#[test]
+fn shrinking_the_window_drops_the_oldest_records() {
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    for i in 0..6 {
+        widget.update(&record(f64::from(i))).unwrap();
+    }
+    widget.set_window(HistoryWindow::Count(2));
+    assert_eq!(temperatures(&widget), [4.0, 5.0]);
+}
+
+#[test]
+fn span_window_keeps_records_not_older_than_the_span() {
+    let clock = ManualClock::new();
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    widget.set_clock(clock.clone());
+    widget.set_window(HistoryWindow::Span(Duration::from_secs(60)));
+    for i in 0..5 {
+        widget.update(&record(f64::from(i))).unwrap();
+        clock.advance(Duration::from_secs(20));
+    }
+    // Taken at 0, 20, 40, 60 and 80 s: the one at 20 s is exactly 60 s old.
+    assert_eq!(temperatures(&widget), [1.0, 2.0, 3.0, 4.0]);
+
+    widget.set_window(HistoryWindow::Span(Duration::from_secs(30)));
+    assert_eq!(temperatures(&widget), [3.0, 4.0]);
+}
+
+#[test]
+fn span_window_times_records_by_their_stamps() {
+    let stamped = |seconds: u64, temperature: f64| {
+        let stamp = Stamp { sequence: seconds, timestamp: UNIX_EPOCH + Duration::from_secs(seconds) };
+        WeatherRecord { stamp: Some(stamp), ..record(temperature) }
+    };
+    // The widget clock stands still, only the stamps move.
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    widget.set_clock(ManualClock::new());
+    widget.set_window(HistoryWindow::Span(Duration::from_secs(90)));
+    for i in 0..4 {
+        widget.update(&stamped(i * 60, i as f64)).unwrap();
+    }
+    assert_eq!(temperatures(&widget), [2.0, 3.0]);
+}
+
+fn at(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.unwrap();
+    assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+}
+
+#[test]
+fn empty_statistics() {
+    let statistics = RollingStatistics::new();
+    assert!(statistics.is_empty());
+    assert_eq!(statistics.mean(), None);
+    assert_eq!(statistics.median(), None);
+    assert_eq!(statistics.variance(), None);
+    assert_eq!(statistics.trend(), None);
+    assert_eq!(statistics.rate_of_change(), None);
+}
+
+#[test]
+fn order_statistics() {
+    let mut statistics = RollingStatistics::new();
+    for (i, &value) in [7.0, 1.0, 4.0, 10.0].iter().enumerate() {
+        statistics.push(at(i as u64), value);
+    }
+    assert_eq!(statistics.min(), Some(1.0));
+    assert_eq!(statistics.max(), Some(10.0));
+    assert_close(statistics.median(), 5.5);
+    assert_close(statistics.percentile(25.0), 3.25);
+    assert_close(statistics.percentile(90.0), 9.1);
+    assert_eq!(statistics.percentile(150.0), Some(10.0));
+}
+
+#[test]
+fn moments() {
+    let mut statistics = RollingStatistics::new();
+    for (i, &value) in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().enumerate() {
+        statistics.push(at(i as u64), value);
+    }
+    assert_close(statistics.mean(), 5.0);
+    assert_close(statistics.variance(), 4.0);
+    assert_close(statistics.std_dev(), 2.0);
+}
+
+#[test]
+fn trend_and_rate_of_change() {
+    let mut statistics = RollingStatistics::new();
+    // A line rising 0.5 per second with noise around it.
+    for &(seconds, value) in &[(0, 10.0), (10, 16.0), (20, 19.0), (30, 26.0)] {
+        statistics.push(at(seconds), value);
+    }
+    assert_close(statistics.trend(), 0.51);
+    assert_close(statistics.rate_of_change(), 16.0 / 30.0);
+
+    let mut statistics = RollingStatistics::new();
+    statistics.push(at(5), 1.0);
+    statistics.push(at(5), 2.0);
+    assert_eq!(statistics.trend(), None);
+    assert_eq!(statistics.rate_of_change(), None);
+}
+
+#[test]
+fn sliding_matches_recomputing() {
+    let values = [3.5, -1.0, 8.25, 8.25, 0.0, 12.0, 5.5, -4.0, 7.0, 2.0, 9.5, 1.0];
+    let mut sliding = RollingStatistics::new();
+    for (i, &value) in values.iter().enumerate() {
+        sliding.push(at(i as u64 * 60), value);
+        if sliding.len() > 4 {
+            sliding.pop_front();
+        }
+        let mut fresh = RollingStatistics::new();
+        let first = (i + 1).saturating_sub(4);
+        for (j, &value) in values.iter().enumerate().take(i + 1).skip(first) {
+            fresh.push(at(j as u64 * 60), value);
+        }
+        assert_eq!(sliding.min(), fresh.min());
+        assert_eq!(sliding.max(), fresh.max());
+        assert_close(sliding.mean(), fresh.mean().unwrap());
+        assert_close(sliding.median(), fresh.median().unwrap());
+        assert_close(sliding.variance(), fresh.variance().unwrap());
+        assert_eq!(sliding.trend().is_some(), fresh.trend().is_some());
+        if let Some(trend) = fresh.trend() {
+            assert_close(sliding.trend(), trend);
+        }
+        assert_eq!(sliding.rate_of_change(), fresh.rate_of_change());
+    }
+}
+
+#[test]
+fn long_sliding_does_not_drift() {
+    let value = |i: u64| (i as f64 * 0.37).sin() * 10.0 + i as f64 * 1e-3;
+    let mut sliding = RollingStatistics::new();
+    let mut steady = RollingStatistics::new();
+    let pushes = 2_000_000;
+    for i in 0..pushes {
+        sliding.push(at(i * 60), value(i));
+        steady.push(at(i * 60), 5.0);
+        if sliding.len() > 10 {
+            sliding.pop_front();
+            steady.pop_front();
+        }
+    }
+    let mut fresh = RollingStatistics::new();
+    for i in pushes - 10..pushes {
+        fresh.push(at(i * 60), value(i));
+    }
+    let relative = |actual: Option<f64>, expected: Option<f64>| {
+        let (actual, expected) = (actual.unwrap(), expected.unwrap());
+        assert!((actual - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+                "{} != {}",
+                actual,
+                expected);
+    };
+    relative(sliding.mean(), fresh.mean());
+    relative(sliding.variance(), fresh.variance());
+    relative(sliding.trend(), fresh.trend());
+    relative(sliding.rate_of_change(), fresh.rate_of_change());
+    assert_eq!(steady.trend(), Some(0.0));
+    assert_eq!(steady.variance(), Some(0.0));
+}
+
+#[test]
+fn widget_statistics_follow_window_and_units() {
+    let clock = ManualClock::new();
+    let mut widget = WidgetStatistic::with_output("Statistic", io::sink());
+    widget.set_clock(clock.clone());
+    widget.set_window(HistoryWindow::Count(3));
+    for &temperature in &[30.0, 10.0, 20.0, 0.0] {
+        widget.update(&record(temperature)).unwrap();
+        clock.advance(Duration::from_secs(3600));
+    }
+    let temperature = widget.statistics(Quantity::Temperature);
+    assert_eq!(temperature.len(), 3);
+    assert_close(temperature.median(), 10.0);
+    assert_close(temperature.trend(), -5.0 / 3600.0);
+    assert!(widget.statistics(Quantity::WindSpeed).is_empty());
+
+    widget.set_units(Units::imperial());
+    let temperature = widget.statistics(Quantity::Temperature);
+    assert_close(temperature.median(), 50.0);
+    assert_close(temperature.std_dev(), 1.8 * (200.0f64 / 3.0).sqrt());
+}
@@ -0,0 +1,90 @@
+extern crate futures;
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::executor::{block_on, LocalPool};
+use futures::task::LocalSpawnExt;
+use futures::StreamExt;
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+
+fn station(count: i32) -> WeatherData<ScriptedSensor> {
+    let script = (0..count)
+        .map(|t| WeatherRecord::from_readings(f64::from(t), 50.0, 760.0).unwrap())
+        .collect();
+    WeatherData::with_sensor(ScriptedSensor::new(script))
+}
+
+fn temperatures(records: Vec<WeatherRecord>) -> Vec<f64> {
+    records.iter().map(|record| record.temperature.celsius()).collect()
+}
+
+#[test]
+fn stream_yields_records_in_order() {
+    let mut weather = station(3);
+    let stream = weather.subscribe_stream(8);
+    while weather.measurements_changed().is_some() {}
+    drop(weather);
+
+    let records = block_on(stream.collect::<Vec<_>>());
+    assert_eq!(temperatures(records), vec![0.0, 1.0, 2.0]);
+}
+
+#[test]
+fn slow_subscriber_lags() {
+    let mut weather = station(5);
+    let mut slow = weather.subscribe_stream(2);
+    let mut fast = weather.subscribe_stream(8);
+    while weather.measurements_changed().is_some() {}
+
+    assert_eq!(slow.lagged(), 3);
+    assert_eq!(slow.pending(), 2);
+    assert_eq!(block_on(slow.next()).unwrap().temperature.celsius(), 3.0);
+    assert_eq!(slow.take_lagged(), 3);
+    assert_eq!(slow.lagged(), 0);
+    assert_eq!(fast.lagged(), 0);
+    assert_eq!(block_on(fast.next()).unwrap().temperature.celsius(), 0.0);
+}
+
+#[test]
+fn dropped_stream_is_unsubscribed() {
+    let mut weather = station(2);
+    let stream = weather.subscribe_stream(8);
+    assert_eq!(weather.measurements_changed().unwrap().delivered, 1);
+    drop(stream);
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.delivered, 0);
+    assert_eq!(report.pruned.len(), 1);
+}
+
+#[test]
+fn consumed_by_local_executor() {
+    let mut weather = station(4);
+    let stream = weather.subscribe_stream(8);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+
+    let mut pool = LocalPool::new();
+    {
+        let seen = seen.clone();
+        pool.spawner()
+            .spawn_local(stream.for_each(move |record| {
+                seen.borrow_mut().push(record.temperature.celsius());
+                futures::future::ready(())
+            }))
+            .unwrap();
+    }
+    pool.run_until_stalled();
+    assert!(seen.borrow().is_empty());
+
+    weather.measurements_changed();
+    weather.measurements_changed();
+    pool.run_until_stalled();
+    assert_eq!(*seen.borrow(), vec![0.0, 1.0]);
+
+    while weather.measurements_changed().is_some() {}
+    drop(weather);
+    pool.run();
+    assert_eq!(*seen.borrow(), vec![0.0, 1.0, 2.0, 3.0]);
+}
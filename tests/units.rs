@@ -0,0 +1,141 @@
+extern crate pattern_observer;
+
+use pattern_observer::units::{format_value, PressureUnit, SpeedUnit, RainfallUnit, TemperatureUnit, Units};
+use pattern_observer::weather::{Humidity, Pressure, Rainfall, Temperature, TemperatureDelta, UvIndex, WeatherRecord,
+                                WindDirection, WindSpeed};
+
+#[test]
+fn temperature_conversions() {
+    let boiling = Temperature::from_celsius(100.0).unwrap();
+    assert_eq!(boiling.fahrenheit(), 212.0);
+    assert_eq!(boiling.kelvin(), 373.15);
+    assert_eq!(Temperature::from_fahrenheit(-40.0).unwrap().celsius(), -40.0);
+    assert_eq!(Temperature::from_kelvin(273.15).unwrap(), Temperature::from_celsius(0.0).unwrap());
+}
+
+#[test]
+fn temperature_differences_have_no_offset() {
+    let difference = Temperature::from_celsius(21.0).unwrap().abs_diff(&Temperature::from_celsius(20.0).unwrap());
+    assert_eq!(difference.celsius(), 1.0);
+    assert_eq!(difference.fahrenheit(), 1.8);
+    assert_eq!(difference.kelvin(), 1.0);
+    assert_eq!(TemperatureDelta::from_fahrenheit(1.8).unwrap(), difference);
+    assert_eq!(TemperatureDelta::from_kelvin(1.0).unwrap(), difference);
+    assert!(TemperatureDelta::from_celsius(f64::NAN).is_err());
+}
+
+#[test]
+fn temperature_round_trips_are_lossless() {
+    let units = [TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit, TemperatureUnit::Kelvin];
+    for hundredths in 26_000..34_000 {
+        let value = f64::from(hundredths) / 100.0;
+        for &from in units.iter() {
+            let temperature = Temperature::new(value, from).unwrap();
+            for &to in units.iter() {
+                let converted = Temperature::new(temperature.value(to), to).unwrap();
+                assert_eq!(converted, temperature);
+            }
+            assert_eq!(temperature.value(from), value);
+        }
+    }
+}
+
+#[test]
+fn pressure_conversions() {
+    let standard = Pressure::from_mmhg(760.0).unwrap();
+    assert_eq!(format_value(standard.hpa()), "1013.25");
+    assert_eq!(format_value(standard.inhg()), "29.92");
+    assert_eq!(Pressure::from_hpa(1013.25).unwrap(), standard);
+}
+
+#[test]
+fn pressure_round_trips_are_lossless() {
+    let units = [PressureUnit::MillimetersOfMercury, PressureUnit::Hectopascals];
+    for tenths in 6000..11000 {
+        let value = f64::from(tenths) / 10.0;
+        for &from in units.iter() {
+            let pressure = Pressure::new(value, from).unwrap();
+            for &to in units.iter() {
+                assert_eq!(Pressure::new(pressure.value(to), to).unwrap(), pressure);
+            }
+            assert_eq!(pressure.value(from), value);
+        }
+    }
+    for hundredths in 2500..3200 {
+        let value = f64::from(hundredths) / 100.0;
+        let pressure = Pressure::from_inhg(value).unwrap();
+        assert_eq!(pressure.inhg(), value);
+        assert_eq!(Pressure::from_hpa(pressure.hpa()).unwrap(), pressure);
+    }
+}
+
+#[test]
+fn out_of_range_values_are_rejected() {
+    assert!(Temperature::from_kelvin(0.0).is_ok());
+    assert!(Temperature::from_kelvin(-0.01).is_err());
+    assert!(Temperature::from_celsius(-273.16).is_err());
+    assert!(Humidity::from_percent(100.0).is_ok());
+    assert!(Humidity::from_percent(100.5).is_err());
+    assert!(Humidity::from_percent(-1.0).is_err());
+    assert!(Pressure::from_hpa(-1.0).is_err());
+    assert!(Temperature::from_celsius(f64::NAN).is_err());
+
+    let error = WeatherRecord::from_readings(20.0, 120.0, 760.0).unwrap_err();
+    assert_eq!(error.to_string(), "humidity 120 % is out of range");
+}
+
+#[test]
+fn unit_systems() {
+    assert_eq!(Units::default().pressure.symbol(), "mmHg");
+    assert_eq!(Units::metric().pressure.symbol(), "hPa");
+    assert_eq!(Units::imperial().temperature.symbol(), "°F");
+}
+
+#[test]
+fn wind_speed_conversions() {
+    let speed = WindSpeed::new(36.0, SpeedUnit::KilometersPerHour).unwrap();
+    assert_eq!(speed.meters_per_second(), 10.0);
+    assert_eq!(format_value(speed.value(SpeedUnit::MilesPerHour)), "22.37");
+    assert_eq!(format_value(speed.value(SpeedUnit::Knots)), "19.44");
+    for tenths in 0..1000 {
+        let value = f64::from(tenths) / 10.0;
+        let speed = WindSpeed::new(value, SpeedUnit::Knots).unwrap();
+        assert_eq!(speed.value(SpeedUnit::Knots), value);
+        assert_eq!(WindSpeed::new(speed.value(SpeedUnit::KilometersPerHour), SpeedUnit::KilometersPerHour).unwrap(),
+                   speed);
+    }
+    assert!(WindSpeed::from_meters_per_second(-0.1).is_err());
+}
+
+#[test]
+fn wind_direction_compass_points() {
+    let compass = |degrees| WindDirection::from_degrees(degrees).unwrap().compass();
+    assert_eq!(compass(0.0), "N");
+    assert_eq!(compass(360.0), "N");
+    assert_eq!(compass(11.2), "N");
+    assert_eq!(compass(11.3), "NNE");
+    assert_eq!(compass(90.0), "E");
+    assert_eq!(compass(250.0), "WSW");
+    assert_eq!(compass(348.8), "N");
+    assert_eq!(WindDirection::from_degrees(360.0).unwrap().degrees(), 0.0);
+    assert!(WindDirection::from_degrees(360.1).is_err());
+    assert!(WindDirection::from_degrees(-1.0).is_err());
+}
+
+#[test]
+fn wind_direction_difference_goes_the_short_way() {
+    let degrees = |value| WindDirection::from_degrees(value).unwrap();
+    assert_eq!(degrees(350.0).abs_diff(&degrees(10.0)).degrees(), 20.0);
+    assert_eq!(degrees(10.0).abs_diff(&degrees(350.0)).degrees(), 20.0);
+    assert_eq!(degrees(90.0).abs_diff(&degrees(270.0)).degrees(), 180.0);
+}
+
+#[test]
+fn rainfall_and_uv_index() {
+    let rain = Rainfall::new(0.12, RainfallUnit::Inches).unwrap();
+    assert_eq!(format_value(rain.millimeters()), "3.05");
+    assert_eq!(rain.value(RainfallUnit::Inches), 0.12);
+    assert!(Rainfall::from_millimeters(-1.0).is_err());
+    assert_eq!(UvIndex::new(7.5).unwrap().value(), 7.5);
+    assert!(UvIndex::new(-0.5).is_err());
+}
@@ -0,0 +1,78 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use pattern_observer::observer::{Observable, Observer, UpdateError};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::shared::SharedWeatherData;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+use pattern_observer::widget::{WidgetCurrent, WidgetStatistic};
+
+fn station(count: i32) -> WeatherData<ScriptedSensor> {
+    let script = (0..count)
+        .map(|t| WeatherRecord::from_readings(f64::from(t), 50.0, 760.0).unwrap())
+        .collect();
+    WeatherData::with_sensor(ScriptedSensor::new(script))
+}
+
+#[test]
+fn caller_can_query_registered_widget() {
+    let mut weather = station(3);
+    let widget = Rc::new(RefCell::new(WidgetStatistic::with_output("Statistic", io::sink())));
+    weather.register_weak(&widget);
+    while weather.measurements_changed().is_some() {}
+    assert_eq!(widget.borrow().len(), 3);
+}
+
+#[test]
+fn dropped_widget_is_pruned() {
+    let mut weather = station(3);
+    let widget = Rc::new(RefCell::new(WidgetCurrent::with_output("Current", io::sink())));
+    let id = weather.register_weak(&widget);
+    weather.measurements_changed();
+    assert_eq!(widget.borrow().current().temperature.celsius(), 0.0);
+
+    drop(widget);
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.pruned, vec![id]);
+    assert_eq!(report.delivered, 0);
+    assert!(weather.remove(id).is_err());
+}
+
+#[test]
+fn borrowed_widget_reports_error() {
+    let mut weather = station(1);
+    let widget = Rc::new(RefCell::new(WidgetCurrent::with_output("Current", io::sink())));
+    weather.register_weak(&widget);
+    let _guard = widget.borrow_mut();
+    let report = weather.measurements_changed().unwrap();
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].name, "<borrowed>");
+}
+
+struct Counter(usize);
+impl Observer<WeatherRecord> for Counter {
+    fn update(&mut self, _: &WeatherRecord) -> Result<(), UpdateError> {
+        self.0 += 1;
+        Ok(())
+    }
+    fn name(&self) -> String {
+        "counter".to_string()
+    }
+}
+
+#[test]
+fn shared_subject_accepts_weak_arc() {
+    let weather = SharedWeatherData::seeded(3);
+    let counter = Arc::new(Mutex::new(Counter(0)));
+    let id = weather.register_weak(&counter);
+    weather.measurements_changed();
+    weather.measurements_changed();
+    assert_eq!(counter.lock().unwrap().0, 2);
+
+    drop(counter);
+    assert_eq!(weather.measurements_changed().unwrap().pruned, vec![id]);
+}
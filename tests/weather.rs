@@ -0,0 +1,258 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use pattern_observer::clock::ManualClock;
+use pattern_observer::data::DataGen;
+use pattern_observer::sensor::{Sensor, ScriptedSensor};
+use pattern_observer::observer::{Observable, Observer, SubscriptionError, UpdateError};
+use pattern_observer::weather::{Deadband, Stamp, WeatherData, WeatherRecord};
+use pattern_observer::widget::{WidgetCurrent, WidgetStatistic};
+
+struct Probe {
+    name: String,
+    records: Rc<RefCell<Vec<WeatherRecord>>>,
+}
+impl Probe {
+    fn new(name: &str) -> (Probe, Rc<RefCell<Vec<WeatherRecord>>>) {
+        let records = Rc::new(RefCell::new(Vec::new()));
+        let probe = Probe {
+            name: name.to_string(),
+            records: records.clone(),
+        };
+        (probe, records)
+    }
+}
+impl Observer<WeatherRecord> for Probe {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        self.records.borrow_mut().push(*record);
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Appends its name to a shared journal on every update.
+struct Tagger {
+    name: String,
+    journal: Rc<RefCell<Vec<String>>>,
+}
+impl Observer<WeatherRecord> for Tagger {
+    fn update(&mut self, _: &WeatherRecord) -> Result<(), UpdateError> {
+        self.journal.borrow_mut().push(self.name.clone());
+        Ok(())
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+fn tagger(name: &str, journal: &Rc<RefCell<Vec<String>>>) -> Box<Tagger> {
+    Box::new(Tagger {
+        name: name.to_string(),
+        journal: journal.clone(),
+    })
+}
+
+#[test]
+fn data_gen_stays_in_range() {
+    for value in DataGen::new(10, 5).take(100) {
+        assert!((10..15).contains(&value));
+    }
+}
+
+#[test]
+fn data_gen_yields_fractions() {
+    let values: Vec<f64> = DataGen::seeded(10.0, 0.5, 1).take(100).collect();
+    assert!(values.iter().all(|value| (10.0..10.5).contains(value)));
+    assert!(values.iter().any(|value| value.fract() != 0.0));
+}
+
+#[test]
+fn seeded_data_gen_is_reproducible() {
+    let first: Vec<i32> = DataGen::seeded(0, 1000, 42).take(50).collect();
+    let second: Vec<i32> = DataGen::seeded(0, 1000, 42).take(50).collect();
+    let other: Vec<i32> = DataGen::seeded(0, 1000, 43).take(50).collect();
+    assert_eq!(first, second);
+    assert!(first != other);
+}
+
+fn collect_records<S: Sensor>(mut weather: WeatherData<S>, count: usize) -> Vec<WeatherRecord> {
+    weather.set_clock(ManualClock::new());
+    let (probe, records) = Probe::new("probe");
+    weather.register(Box::new(probe));
+    for _ in 0..count {
+        weather.measurements_changed();
+    }
+    let result = records.borrow().clone();
+    result
+}
+
+#[test]
+fn seeded_weather_data_is_reproducible() {
+    let first = collect_records(WeatherData::seeded(7), 20);
+    let second = collect_records(WeatherData::seeded(7), 20);
+    let other = collect_records(WeatherData::seeded(8), 20);
+    assert_eq!(first, second);
+    assert!(first != other);
+}
+
+#[test]
+fn notify_delivers_record() {
+    let mut weather = WeatherData::new();
+    let (probe, records) = Probe::new("probe");
+    weather.register(Box::new(probe));
+
+    let record = WeatherRecord::from_readings(1.0, 2.0, 3.0).unwrap();
+    weather.notify(record);
+    assert_eq!(*records.borrow(), vec![record]);
+}
+
+#[test]
+fn measurements_changed_generates_records_in_range() {
+    let mut weather = WeatherData::new();
+    let (probe, records) = Probe::new("probe");
+    weather.register(Box::new(probe));
+
+    for _ in 0..20 {
+        weather.measurements_changed();
+    }
+    let records = records.borrow();
+    assert_eq!(records.len(), 20);
+    for record in records.iter() {
+        assert!((10.0..20.0).contains(&record.temperature.celsius()));
+        assert!((40.0..100.0).contains(&record.humidity.percent()));
+        assert!((700.0..790.0).contains(&record.pressure.mmhg()));
+        let tenths = record.temperature.celsius() * 10.0;
+        assert!((tenths - tenths.round()).abs() < 1e-9);
+    }
+    assert!(records.iter().any(|record| record.temperature.celsius().fract() != 0.0));
+}
+
+#[test]
+fn measurements_are_stamped() {
+    let clock = ManualClock::new();
+    let mut weather = WeatherData::seeded(1);
+    weather.set_clock(clock.clone());
+    let (probe, records) = Probe::new("probe");
+    weather.register(Box::new(probe));
+
+    for _ in 0..3 {
+        weather.measurements_changed();
+        clock.advance(Duration::from_secs(60));
+    }
+    weather.notify(WeatherRecord::new());
+    let stamps: Vec<_> = records.borrow().iter().map(|record| record.stamp).collect();
+    assert_eq!(stamps,
+               vec![Some(Stamp {
+                        sequence: 1,
+                        timestamp: UNIX_EPOCH,
+                    }),
+                    Some(Stamp {
+                        sequence: 2,
+                        timestamp: UNIX_EPOCH + Duration::from_secs(60),
+                    }),
+                    Some(Stamp {
+                        sequence: 3,
+                        timestamp: UNIX_EPOCH + Duration::from_secs(120),
+                    }),
+                    None]);
+}
+
+#[test]
+fn suppressed_records_use_up_sequence_numbers() {
+    let changed = WeatherRecord::from_readings(1.0, 0.0, 0.0).unwrap();
+    let script = vec![WeatherRecord::new(), WeatherRecord::new(), changed];
+    let mut weather = WeatherData::with_sensor(ScriptedSensor::new(script));
+    weather.set_deadband(Some(Deadband::default()));
+    let (probe, records) = Probe::new("probe");
+    weather.register(Box::new(probe));
+    while weather.measurements_changed().is_some() {}
+
+    let sequences: Vec<_> = records.borrow().iter().map(|record| record.stamp.unwrap().sequence).collect();
+    assert_eq!(sequences, vec![1, 3]);
+}
+
+#[test]
+fn removed_observer_is_not_notified() {
+    let mut weather = WeatherData::new();
+    let (probe, records) = Probe::new("probe");
+    let id = weather.register(Box::new(probe));
+    assert_eq!(weather.remove(id), Ok(()));
+
+    weather.measurements_changed();
+    assert!(records.borrow().is_empty());
+}
+
+#[test]
+fn observers_with_same_name_coexist() {
+    let mut weather = WeatherData::new();
+    let (first, first_records) = Probe::new("probe");
+    let (second, second_records) = Probe::new("probe");
+    let first_id = weather.register(Box::new(first));
+    let second_id = weather.register(Box::new(second));
+    assert!(first_id != second_id);
+
+    weather.measurements_changed();
+    assert_eq!(first_records.borrow().len(), 1);
+    assert_eq!(second_records.borrow().len(), 1);
+
+    weather.remove(first_id).unwrap();
+    weather.measurements_changed();
+    assert_eq!(first_records.borrow().len(), 1);
+    assert_eq!(second_records.borrow().len(), 2);
+}
+
+#[test]
+fn removing_unknown_subscription_fails() {
+    let mut weather = WeatherData::new();
+    let (probe, _) = Probe::new("probe");
+    let id = weather.register(Box::new(probe));
+    weather.remove(id).unwrap();
+    assert_eq!(weather.remove(id), Err(SubscriptionError::Unknown(id)));
+
+    let mut other = WeatherData::new();
+    assert_eq!(other.remove(id), Err(SubscriptionError::Unknown(id)));
+}
+
+#[test]
+fn widgets_accept_updates() {
+    let mut weather = WeatherData::new();
+    weather.register(Box::new(WidgetCurrent::new("Current Widget")));
+    weather.register(Box::new(WidgetStatistic::new("Statistic Widget")));
+    for _ in 0..3 {
+        weather.measurements_changed();
+    }
+}
+
+#[test]
+fn notify_follows_registration_order() {
+    let mut weather = WeatherData::new();
+    let journal = Rc::new(RefCell::new(Vec::new()));
+    let names = ["a", "b", "c", "d", "e"];
+    for name in names.iter() {
+        weather.register(tagger(name, &journal));
+    }
+    for _ in 0..3 {
+        journal.borrow_mut().clear();
+        weather.measurements_changed();
+        assert_eq!(*journal.borrow(), names);
+    }
+}
+
+#[test]
+fn notify_respects_priorities() {
+    let mut weather = WeatherData::new();
+    let journal = Rc::new(RefCell::new(Vec::new()));
+    weather.register(tagger("display 1", &journal));
+    weather.register_with_priority(tagger("late", &journal), -1);
+    weather.register_with_priority(tagger("logger 1", &journal), 10);
+    weather.register(tagger("display 2", &journal));
+    weather.register_with_priority(tagger("logger 2", &journal), 10);
+
+    weather.measurements_changed();
+    assert_eq!(*journal.borrow(),
+               ["logger 1", "logger 2", "display 1", "display 2", "late"]);
+}
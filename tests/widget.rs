@@ -0,0 +1,171 @@
+extern crate pattern_observer;
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use pattern_observer::clock::ManualClock;
+use pattern_observer::observer::Observable;
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{Temperature, UvIndex, WeatherData, WeatherRecord, WindDirection, WindSpeed};
+use pattern_observer::units::Units;
+use pattern_observer::widget::{WidgetCurrent, WidgetStatistic};
+
+#[derive(Clone, Default)]
+struct Screen(Rc<RefCell<Vec<u8>>>);
+impl Screen {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn record(temperature: f64, humidity: f64, pressure: f64) -> WeatherRecord {
+    WeatherRecord::from_readings(temperature, humidity, pressure).unwrap()
+}
+
+// Station whose clock stands still at the Unix epoch.
+fn station(script: Vec<WeatherRecord>) -> WeatherData<ScriptedSensor> {
+    let mut weather = WeatherData::with_sensor(ScriptedSensor::new(script));
+    weather.set_clock(ManualClock::new());
+    weather
+}
+
+#[test]
+fn current_widget_output() {
+    let screen = Screen::default();
+    let mut weather = station(vec![record(12.0, 45.0, 760.0)]);
+    weather.register(Box::new(WidgetCurrent::with_output("Current", screen.clone())));
+    weather.measurements_changed();
+
+    assert_eq!(screen.text(),
+               "Current\n\tTime\t\t: 1970-01-01 00:00:00 (#1)\n\tTemperature\t: 12 °C\n\tHumid\t\t: 45 %\n\tPress\t\t: 760 mmHg\n");
+}
+
+#[test]
+fn statistic_widget_output() {
+    let screen = Screen::default();
+    let script = vec![record(10.0, 40.0, 750.0), record(14.0, 50.0, 760.0)];
+    let mut weather = station(script);
+    weather.register(Box::new(WidgetStatistic::with_output("Statistic", screen.clone())));
+    weather.measurements_changed();
+    weather.measurements_changed();
+
+    let text = screen.text();
+    let last = text.lines().skip(5).collect::<Vec<_>>();
+    assert_eq!(last,
+               ["Statistic",
+                "\tTime\t\t: 1970-01-01 00:00:00 (#2)",
+                "\tTemperature (min/max/avg)\t: 10 / 14 / 12 °C",
+                "\tHumidity (min/max/avg) \t\t: 40 / 50 / 45 %",
+                "\tPressure (min/max/avg) \t\t: 750 / 760 / 755 mmHg"]);
+}
+
+#[test]
+fn current_widget_in_imperial_units() {
+    let screen = Screen::default();
+    let mut widget = WidgetCurrent::with_output("Current", screen.clone());
+    widget.set_units(Units::imperial());
+    let mut weather = station(vec![record(21.4, 45.0, 760.0)]);
+    weather.register(Box::new(widget));
+    weather.measurements_changed();
+
+    assert_eq!(screen.text(),
+               "Current\n\tTime\t\t: 1970-01-01 00:00:00 (#1)\n\tTemperature\t: 70.52 °F\n\tHumid\t\t: 45 %\n\tPress\t\t: 29.92 inHg\n");
+}
+
+#[test]
+fn statistic_widget_in_metric_units() {
+    let screen = Screen::default();
+    let mut widget = WidgetStatistic::with_output("Statistic", screen.clone());
+    widget.set_units(Units::metric());
+    let script = vec![record(-5.0, 40.0, 750.0), record(5.0, 50.0, 760.0)];
+    let mut weather = station(script);
+    weather.register(Box::new(widget));
+    weather.measurements_changed();
+    weather.measurements_changed();
+
+    let text = screen.text();
+    let last = text.lines().skip(5).collect::<Vec<_>>();
+    assert_eq!(last,
+               ["Statistic",
+                "\tTime\t\t: 1970-01-01 00:00:00 (#2)",
+                "\tTemperature (min/max/avg)\t: -5 / 5 / 0 °C",
+                "\tHumidity (min/max/avg) \t\t: 40 / 50 / 45 %",
+                "\tPressure (min/max/avg) \t\t: 999.92 / 1013.25 / 1006.58 hPa"]);
+}
+
+#[test]
+fn widgets_show_record_time_and_sequence() {
+    let screen = Screen::default();
+    let clock = ManualClock::starting_at(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    let mut weather = station(vec![record(12.0, 45.0, 760.0), record(13.0, 45.0, 760.0)]);
+    weather.set_clock(clock.clone());
+    weather.register(Box::new(WidgetCurrent::with_output("Current", screen.clone())));
+    weather.measurements_changed();
+    clock.advance(Duration::from_secs(90));
+    weather.measurements_changed();
+
+    let text = screen.text();
+    let times = text.lines().filter(|line| line.starts_with("\tTime")).collect::<Vec<_>>();
+    assert_eq!(times,
+               ["\tTime\t\t: 2023-11-14 22:13:20 (#1)", "\tTime\t\t: 2023-11-14 22:14:50 (#2)"]);
+}
+
+#[test]
+fn hand_made_records_are_shown_without_time() {
+    let screen = Screen::default();
+    let mut weather = station(Vec::new());
+    weather.register(Box::new(WidgetCurrent::with_output("Current", screen.clone())));
+    weather.notify(record(12.0, 45.0, 760.0));
+
+    assert!(!screen.text().contains("Time"));
+}
+
+fn windy(temperature: f64, speed: f64, direction: f64) -> WeatherRecord {
+    WeatherRecord {
+        wind_speed: Some(WindSpeed::from_meters_per_second(speed).unwrap()),
+        wind_direction: Some(WindDirection::from_degrees(direction).unwrap()),
+        dew_point: Some(Temperature::from_celsius(temperature - 5.0).unwrap()),
+        ..record(temperature, 45.0, 760.0)
+    }
+}
+
+#[test]
+fn current_widget_renders_present_channels() {
+    let screen = Screen::default();
+    let script = vec![WeatherRecord { uv_index: Some(UvIndex::new(3.0).unwrap()), ..windy(12.0, 3.5, 250.0) }];
+    let mut weather = station(script);
+    weather.register(Box::new(WidgetCurrent::with_output("Current", screen.clone())));
+    weather.measurements_changed();
+
+    assert_eq!(screen.text().lines().skip(5).collect::<Vec<_>>(),
+               ["\tWind\t\t: 3.5 m/s", "\tWind from\t: 250° WSW", "\tUV index\t: 3", "\tDew point\t: 7 °C"]);
+}
+
+#[test]
+fn statistic_widget_renders_present_channels() {
+    let screen = Screen::default();
+    let mut widget = WidgetStatistic::with_output("Statistic", screen.clone());
+    widget.set_units(Units::metric());
+    let script = vec![windy(10.0, 2.0, 90.0), record(20.0, 45.0, 760.0), windy(14.0, 4.0, 180.0)];
+    let mut weather = station(script);
+    weather.register(Box::new(widget));
+    while weather.measurements_changed().is_some() {}
+
+    let text = screen.text();
+    let last = text.lines().rev().take(3).collect::<Vec<_>>();
+    assert_eq!(last,
+               ["\tDew point (min/max/avg)\t: 5 / 9 / 7 °C",
+                "\tWind (min/max/avg) \t\t: 7.2 / 14.4 / 10.8 km/h",
+                "\tPressure (min/max/avg) \t\t: 1013.25 / 1013.25 / 1013.25 hPa"]);
+}
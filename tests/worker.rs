@@ -0,0 +1,163 @@
+extern crate pattern_observer;
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use pattern_observer::observer::{Observable, Observer, UpdateError};
+use pattern_observer::sensor::ScriptedSensor;
+use pattern_observer::weather::{WeatherData, WeatherRecord};
+use pattern_observer::worker::{Overflow, WorkerObserver};
+
+/// Records values; waits for a token from `gate` before handling each one, if gated.
+struct Recorder {
+    values: Values,
+    gate: Option<Receiver<()>>,
+}
+impl Observer<i32> for Recorder {
+    fn update(&mut self, value: &i32) -> Result<(), UpdateError> {
+        if let Some(ref gate) = self.gate {
+            let _ = gate.recv();
+        }
+        if *value < 0 {
+            return Err(format!("negative value {}", value).into());
+        }
+        if *value == 666 {
+            panic!("cursed value");
+        }
+        self.values.lock().unwrap().push(*value);
+        Ok(())
+    }
+    fn name(&self) -> String {
+        "recorder".to_string()
+    }
+}
+type Values = Arc<Mutex<Vec<i32>>>;
+
+fn recorder(gated: bool) -> (Recorder, Values, Option<Sender<()>>) {
+    let values = Arc::new(Mutex::new(Vec::new()));
+    let (sender, gate) = if gated {
+        let (sender, receiver) = mpsc::channel();
+        (Some(sender), Some(receiver))
+    } else {
+        (None, None)
+    };
+    let recorder = Recorder {
+        values: values.clone(),
+        gate,
+    };
+    (recorder, values, sender)
+}
+
+#[test]
+fn delivers_everything_in_order() {
+    let (recorder, values, _) = recorder(false);
+    let mut worker = WorkerObserver::spawn(recorder, 4, Overflow::Block);
+    for value in 0..100 {
+        worker.update(&value).unwrap();
+    }
+    assert!(worker.join().is_empty());
+    assert_eq!(*values.lock().unwrap(), (0..100).collect::<Vec<_>>());
+}
+
+/// Fills a capacity 2 queue behind a blocked worker, then pushes one more value.
+fn overflow(policy: Overflow) -> (Vec<i32>, usize) {
+    let (recorder, values, gate) = recorder(true);
+    let mut worker = WorkerObserver::spawn(recorder, 2, policy);
+    let control = worker.control();
+    worker.update(&0).unwrap();
+    while control.pending() > 0 {
+        thread::yield_now();
+    }
+    for value in 1..4 {
+        worker.update(&value).unwrap();
+    }
+    drop(gate);
+    worker.flush();
+    let values = values.lock().unwrap().clone();
+    (values, control.dropped())
+}
+
+#[test]
+fn drop_newest_discards_incoming_value() {
+    assert_eq!(overflow(Overflow::DropNewest), (vec![0, 1, 2], 1));
+}
+
+#[test]
+fn drop_oldest_discards_queued_value() {
+    assert_eq!(overflow(Overflow::DropOldest), (vec![0, 2, 3], 1));
+}
+
+#[test]
+fn block_waits_for_free_slot() {
+    let (recorder, values, gate) = recorder(true);
+    let gate = gate.unwrap();
+    let mut worker = WorkerObserver::spawn(recorder, 1, Overflow::Block);
+    let releaser = thread::spawn(move || {
+        for _ in 0..5 {
+            thread::sleep(Duration::from_millis(5));
+            gate.send(()).unwrap();
+        }
+    });
+    for value in 0..5 {
+        worker.update(&value).unwrap();
+    }
+    worker.flush();
+    releaser.join().unwrap();
+    assert_eq!(worker.control().dropped(), 0);
+    assert_eq!(*values.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn errors_are_collected() {
+    let (recorder, values, _) = recorder(false);
+    let mut worker = WorkerObserver::spawn(recorder, 4, Overflow::Block);
+    let control = worker.control();
+    for value in [1, -2, 3].iter() {
+        worker.update(value).unwrap();
+    }
+    worker.flush();
+    let errors = control.take_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "negative value -2");
+    assert_eq!(*values.lock().unwrap(), vec![1, 3]);
+}
+
+#[test]
+fn panic_stops_the_worker() {
+    let (recorder, _, _) = recorder(false);
+    let mut worker = WorkerObserver::spawn(recorder, 4, Overflow::Block);
+    worker.update(&666).unwrap();
+    worker.flush();
+    assert!(worker.control().is_stopped());
+    assert!(worker.update(&1).is_err());
+    let errors = worker.join();
+    assert_eq!(errors[0].to_string(), "recorder panicked: cursed value");
+}
+
+struct Temperatures(Arc<Mutex<Vec<f64>>>);
+impl Observer<WeatherRecord> for Temperatures {
+    fn update(&mut self, record: &WeatherRecord) -> Result<(), UpdateError> {
+        thread::sleep(Duration::from_millis(1));
+        self.0.lock().unwrap().push(record.temperature.celsius());
+        Ok(())
+    }
+    fn name(&self) -> String {
+        "temperatures".to_string()
+    }
+}
+
+#[test]
+fn removing_from_subject_drains_and_joins() {
+    let script = (0..20)
+        .map(|t| WeatherRecord::from_readings(f64::from(t), 50.0, 760.0).unwrap())
+        .collect();
+    let mut weather = WeatherData::with_sensor(ScriptedSensor::new(script));
+    let temperatures = Arc::new(Mutex::new(Vec::new()));
+    let worker = WorkerObserver::spawn(Temperatures(temperatures.clone()), 32, Overflow::Block);
+    let id = weather.register(Box::new(worker));
+    while weather.measurements_changed().is_some() {}
+    weather.remove(id).unwrap();
+    assert_eq!(*temperatures.lock().unwrap(), (0..20).map(f64::from).collect::<Vec<_>>());
+}